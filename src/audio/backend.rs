@@ -1,88 +1,1658 @@
 use crate::AppError;
-use log::{debug, error};
-use rodio::{Decoder, OutputStream, Sink};
-use std::sync::mpsc;
+use log::{debug, error, info, warn};
+use rodio::{Sink, Source};
+use std::io::{Seek, SeekFrom};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, RwLock};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::oneshot::error::TryRecvError;
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::AbortHandle;
 
-use crate::models::audio::BackendState;
+use crate::audio::deck::Decks;
+use crate::audio::decoder::{StreamDecoder, StreamError};
+use crate::audio::dsp::{Dsp, DspControl};
+use crate::audio::fade::{Fade, FadeControl};
+use crate::audio::loudness::{GainControl, Normalize};
+use crate::audio::output::Output;
+use crate::audio::queue::Queue;
+use crate::audio::reader::Reader;
+use crate::audio::source::{SourceCursor, mark_heard};
+use crate::audio::tags::read_replay_gain;
+use crate::models::audio::{
+    BackendState, CrossfadeConfig, EndReason, NormalizationConfig, OutputKind, PlayOrigin,
+    PlaybackState, PlayerEvent, PlayerStatus, QueueSource, ReplayGain,
+};
+use crate::models::dsp::DspSettings;
+use crate::models::song::{Song, SongUrl, SoundQuality};
+use crate::service::song::get_song_url;
+use crate::storage::loudness::get_loudness_store;
+use crate::storage::progress::get_progress_store;
+use crate::storage::queue::get_queue_store;
+use crate::storage::settings::get_settings;
+
+/// 检查播放是否结束的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// 待播歌曲少于该数量时向来源请求更多
+const REFILL_THRESHOLD: usize = 2;
+/// 向来源请求失败后的重试间隔
+const REFILL_RETRY: Duration = Duration::from_secs(10);
+/// 电台节目收听进度的保存间隔
+const PROGRESS_SAVE_INTERVAL: Duration = Duration::from_secs(10);
+/// 距离曲目结束多久时预加载下一首，开启淡入淡出时再提前相应时长
+const PRELOAD_AHEAD: Duration = Duration::from_secs(15);
+const CROSSFADE_SETTING: &str = "crossfade";
+const NORMALIZATION_SETTING: &str = "normalization";
+const DSP_SETTING: &str = "dsp";
+const OUTPUT_SETTING: &str = "output";
+/// 事件通道容量，处理过慢的订阅者会丢失最旧的事件
+const EVENT_CAPACITY: usize = 256;
+
+/// 获取歌曲的播放地址，测试中使用本地服务器上的地址
+async fn resolve_url(id: u64) -> Result<SongUrl, AppError> {
+    #[cfg(test)]
+    if let Some(url) = crate::testing::song_url(id) {
+        return Ok(url);
+    }
+    get_song_url(id, SoundQuality::ExHigh).await
+}
+
+/// 保存的淡入淡出设置
+pub fn crossfade_config() -> CrossfadeConfig {
+    get_settings().get_or_default(CROSSFADE_SETTING)
+}
+
+/// 保存的音效设置
+pub fn dsp_settings() -> DspSettings {
+    get_settings().get_or_default(DSP_SETTING)
+}
+
+/// 解码后依次经过归一化与音效处理的音源
+type TrackSource = Dsp<Normalize<StreamDecoder>>;
 
 pub struct AudioBackend {
     pub command_sender: mpsc::Sender<BackendState>,
+    events: broadcast::Sender<PlayerEvent>,
+    status: Arc<RwLock<PlayerStatus>>,
+}
+
+struct CurrentTrack {
+    song: Song,
+    origin: PlayOrigin,
+    listened: Duration,
+    /// 最近一次记录的播放位置
+    position: Duration,
+}
+
+/// 提前准备好的下一首
+struct Preloaded {
+    song: Song,
+    reader: Reader,
+    size: Option<u64>,
+    duration: Option<Duration>,
+    control: Arc<FadeControl>,
+    gain: Arc<GainControl>,
+    replay_gain: Option<ReplayGain>,
+    error: StreamError,
+    /// 等待淡入的音源；无缝衔接时已直接排入主 Sink，这里为空
+    pending: Option<TrackSource>,
+    /// 与当前曲目交叉的时长
+    crossfade: Duration,
+}
+
+/// 在后台打开的下一首
+struct Opened {
+    url: SongUrl,
+    reader: Reader,
+    decoder: StreamDecoder,
+    tags: Option<ReplayGain>,
+}
+
+/// 正在后台解析地址并等待首批数据的下一首
+struct Preloading {
+    song: Song,
+    result: oneshot::Receiver<Result<Opened, AppError>>,
+    task: AbortHandle,
+}
+
+/// 由播放队列驱动的播放状态
+#[derive(Default)]
+struct PlayerState {
+    queue: Queue,
+    origin: PlayOrigin,
+    source: Option<SourceCursor>,
+    /// 当前曲目来自播放队列，播放结束后需要自动切到下一首
+    active: bool,
+    current: Option<CurrentTrack>,
+    /// 最近开始播放的歌曲，用作歌曲电台的种子
+    last_played: Option<u64>,
+    last_tick: Option<Instant>,
+    last_refill_error: Option<Instant>,
+    last_progress_save: Option<Instant>,
+    /// 未结束的暂停请求数，以及暂停前是否在播放
+    suspended: u32,
+    resume_after_suspend: bool,
+    /// 当前曲目的数据来源，用于报告缓冲进度
+    reader: Option<Reader>,
+    /// 当前曲目的数据流提前结束时的错误
+    stream_error: Option<StreamError>,
+    /// 当前曲目的淡出控制与归一化增益
+    fade: Option<Arc<FadeControl>>,
+    gain: Option<Arc<GainControl>>,
+    replay_gain: Option<ReplayGain>,
+    preloading: Option<Preloading>,
+    preloaded: Option<Preloaded>,
+    /// 音频加载完成前请求的跳转，以及要跳转的歌曲
+    pending_seek: Option<(u64, Duration)>,
+    /// 预加载失败的歌曲，避免每次轮询都重试
+    preload_failed: Option<u64>,
+    crossfade: CrossfadeConfig,
+    normalization: NormalizationConfig,
+    /// 所有曲目共享，修改后正在播放的音源立即生效
+    dsp: Arc<DspControl>,
+    /// 上次保存并通知界面时的队列版本，启动后还未同步时为 `None`
+    queue_revision: Option<u64>,
+    queue_songs: Arc<[Song]>,
+    /// 当前输出设备的名称
+    output: String,
+    total_bytes: Option<u64>,
+    duration: Option<Duration>,
+    state: PlaybackState,
+    last_buffered: u64,
+    events: Option<broadcast::Sender<PlayerEvent>>,
+    status: Arc<RwLock<PlayerStatus>>,
+}
+
+impl PlayerState {
+    fn emit(&self, event: PlayerEvent) {
+        if let Some(events) = &self.events {
+            // 没有订阅者时发送失败，忽略即可
+            let _ = events.send(event);
+        }
+    }
+
+    /// 撤回预加载的曲目，已经排入 Sink 的会在轮到它时立即结束
+    fn cancel_preload(&mut self) {
+        if let Some(preloading) = self.preloading.take() {
+            debug!("Cancelled preloading of {}", preloading.song.id);
+            preloading.task.abort();
+        }
+        if let Some(preloaded) = self.preloaded.take() {
+            debug!("Dropped preloaded track {}", preloaded.song.id);
+            preloaded.control.cancel();
+        }
+        self.preload_failed = None;
+    }
+
+    /// 队列变化后预加载的曲目可能不再是下一首
+    fn check_preload(&mut self) {
+        let preloading = self.preloading.as_ref().map(|p| p.song.id);
+        let Some(id) = preloading.or(self.preloaded.as_ref().map(|p| p.song.id)) else {
+            return;
+        };
+        if self.queue.upcoming().is_none_or(|song| song.id != id) {
+            self.cancel_preload();
+        }
+    }
+
+    /// 队列有变化时保存并通知界面
+    fn sync_queue(&mut self) {
+        let revision = self.queue.revision();
+        if self.queue_revision == Some(revision) {
+            return;
+        }
+        // 启动时读取的队列不需要再次保存
+        if self.queue_revision.is_some() {
+            get_queue_store().save(&self.queue);
+        }
+        self.queue_revision = Some(revision);
+        self.queue_songs = self.queue.songs().cloned().collect();
+        self.emit(PlayerEvent::QueueChanged {
+            songs: self.queue_songs.clone(),
+            index: self.queue.current_index(),
+            mode: self.queue.mode(),
+        });
+    }
+
+    /// 数据流因读取或解码出错提前结束时上报错误，每个错误只上报一次
+    fn report_stream_error(&mut self) -> bool {
+        let Some(e) = self.stream_error.as_ref().and_then(StreamError::take) else {
+            return false;
+        };
+        warn!("Playback ended early: {}", e);
+        self.emit(PlayerEvent::Error(e.to_string()));
+        true
+    }
+
+    /// 当前曲目的数据流已经结束，出错提前结束的按中断上报
+    fn finish_track(&mut self) {
+        let reason = if self.report_stream_error() {
+            EndReason::Interrupted
+        } else {
+            EndReason::Finished
+        };
+        self.end_track(reason);
+    }
+
+    fn set_state(&mut self, state: PlaybackState) {
+        if self.state != state {
+            self.state = state;
+            self.emit(PlayerEvent::StateChanged(state));
+        }
+    }
+
+    /// 根据 sink 推断播放状态，加载中的状态由调用方显式设置
+    fn sync_state(&mut self, sink: &Sink) {
+        let state = if sink.empty() {
+            PlaybackState::Stopped
+        } else if sink.is_paused() {
+            PlaybackState::Paused
+        } else {
+            PlaybackState::Playing
+        };
+        self.set_state(state);
+    }
+
+    /// 更新状态快照，并发送播放位置与缓冲进度
+    fn publish(&mut self, sink: &Sink) {
+        self.sync_state(sink);
+        let position = if sink.empty() {
+            Duration::ZERO
+        } else {
+            sink.get_pos()
+        };
+        let buffered = self.reader.as_ref().map_or(0, Reader::buffered_end);
+        // 实际的数据长度比接口返回的文件大小可靠
+        if let Some(length) = self.reader.as_ref().and_then(Reader::len) {
+            self.total_bytes = Some(length);
+        }
+
+        if let Ok(mut status) = self.status.write() {
+            *status = PlayerStatus {
+                state: self.state,
+                song: self.current.as_ref().map(|c| c.song.clone()),
+                origin: self.origin.clone(),
+                position,
+                duration: self.duration,
+                buffered,
+                total_bytes: self.total_bytes,
+                buffered_percent: self.reader.as_ref().and_then(Reader::buffered_percent),
+                buffered_ranges: self
+                    .reader
+                    .as_ref()
+                    .map(Reader::buffered_ranges)
+                    .unwrap_or_default(),
+                volume: sink.volume(),
+                speed: sink.speed(),
+                mode: self.queue.mode(),
+                queue: self.queue_songs.clone(),
+                queue_index: self.queue.current_index(),
+                output: self.output.clone(),
+            };
+        }
+
+        if self.state == PlaybackState::Playing {
+            self.emit(PlayerEvent::Position {
+                position,
+                duration: self.duration,
+            });
+        }
+        if buffered != self.last_buffered {
+            self.last_buffered = buffered;
+            self.emit(PlayerEvent::Buffering {
+                percent: self.reader.as_ref().and_then(Reader::buffered_percent),
+            });
+        }
+    }
+
+    /// 累计实际收听的时长，暂停期间不计入
+    fn tick(&mut self, sink: &Sink) {
+        let now = Instant::now();
+        // 数据流出错后 sink 中剩下的位置已经不属于当前曲目
+        let failed = self.stream_error.as_ref().is_some_and(StreamError::is_set);
+        if let Some(current) = self.current.as_mut() {
+            if !sink.empty() && !failed {
+                current.position = sink.get_pos();
+                if let Some(last) = self.last_tick.filter(|_| !sink.is_paused()) {
+                    current.listened += now - last;
+                }
+            }
+        }
+        self.last_tick = Some(now);
+
+        if self
+            .last_progress_save
+            .is_none_or(|at| at.elapsed() >= PROGRESS_SAVE_INTERVAL)
+        {
+            self.save_progress(sink);
+        }
+    }
+
+    /// 记录电台节目的播放位置，下次播放时从这里继续
+    fn save_progress(&mut self, sink: &Sink) {
+        let Some(current) = &self.current else {
+            return;
+        };
+        if current.origin.is_program() && !sink.empty() {
+            get_progress_store().save(current.song.id, sink.get_pos());
+            self.last_progress_save = Some(Instant::now());
+        }
+    }
+
+    fn restore_progress(&mut self, sink: &Sink) {
+        let Some(current) = &self.current else {
+            return;
+        };
+        // 加载前请求的跳转优先于保存的进度，切到其他歌曲后作废
+        if let Some((song, position)) = self.pending_seek.take() {
+            if song == current.song.id {
+                match sink.try_seek(position) {
+                    Ok(()) => debug!("Applied queued seek to {:?}", position),
+                    Err(e) => warn!("Failed to apply queued seek: {}", e),
+                }
+                return;
+            }
+        }
+        if !current.origin.is_program() {
+            return;
+        }
+        if let Some(position) = get_progress_store().get(current.song.id) {
+            match sink.try_seek(position) {
+                Ok(()) => debug!("Resumed {} at {:?}", current.song.id, position),
+                Err(e) => warn!("Failed to resume {}: {}", current.song.id, e),
+            }
+        }
+    }
+
+    fn start_track(&mut self, song: Song) {
+        self.preload_failed = None;
+        mark_heard(song.id);
+        self.last_played = Some(song.id);
+        self.emit(PlayerEvent::TrackStarted {
+            song: song.clone(),
+            origin: self.origin.clone(),
+        });
+        self.current = Some(CurrentTrack {
+            song,
+            origin: self.origin.clone(),
+            listened: Duration::ZERO,
+            position: Duration::ZERO,
+        });
+    }
+
+    fn end_track(&mut self, reason: EndReason) {
+        if let Some(current) = self.current.take() {
+            // 只有完整播完的节目才清除进度，中途出错或切走时保留最后的位置
+            if current.origin.is_program() {
+                if reason == EndReason::Finished {
+                    get_progress_store().remove(current.song.id);
+                } else if !current.position.is_zero() {
+                    get_progress_store().save(current.song.id, current.position);
+                }
+            }
+            self.emit(PlayerEvent::TrackEnded {
+                song: current.song,
+                origin: current.origin,
+                listened: current.listened,
+                reason,
+            });
+        }
+    }
 }
 
 // 优化过的狗屎
 impl AudioBackend {
     pub fn new() -> Result<Self, AppError> {
+        Self::with_output(get_settings().get_or_default(OUTPUT_SETTING))
+    }
+
+    /// 使用指定的输出方式，没有声卡的环境可以用无声输出或 WAV 文件
+    pub fn with_output(output: OutputKind) -> Result<Self, AppError> {
         let (command_sender, command_receiver) = mpsc::channel();
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let status = Arc::new(RwLock::new(PlayerStatus::default()));
 
-        thread::spawn(move || {
-            let rt = tokio::runtime::Runtime::new().unwrap();
-            if let Err(e) = rt.block_on(Self::audio_thread_main(command_receiver)) {
-                error!("Audio thread terminated with error: {}", e);
-            }
-        });
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| AppError::Thread(format!("Failed to create audio runtime: {}", e)))?;
+        thread::Builder::new()
+            .name("audio".to_string())
+            .spawn({
+                let events = events.clone();
+                let status = status.clone();
+                move || {
+                    let main = Self::audio_thread_main(command_receiver, output, events, status);
+                    if let Err(e) = rt.block_on(main) {
+                        error!("Audio thread terminated with error: {}", e);
+                    }
+                }
+            })
+            .map_err(|e| AppError::Thread(format!("Failed to spawn audio thread: {}", e)))?;
 
-        Ok(Self { command_sender })
+        Ok(Self {
+            command_sender,
+            events,
+            status,
+        })
     }
 
-    async fn audio_thread_main(receiver: mpsc::Receiver<BackendState>) -> Result<(), AppError> {
-        let (_stream, stream_handle) = OutputStream::try_default()?;
-        let sink = Sink::try_new(&stream_handle)?;
+    /// 播放线程无法启动时使用，命令会被丢弃，界面照常运行
+    pub fn dummy() -> Self {
+        let (command_sender, _) = mpsc::channel();
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        Self {
+            command_sender,
+            events,
+            status: Arc::default(),
+        }
+    }
+
+    /// 订阅播放事件，只能收到订阅之后发生的事件
+    pub fn subscribe(&self) -> broadcast::Receiver<PlayerEvent> {
+        self.events.subscribe()
+    }
+
+    /// 当前播放状态的快照，订阅事件前可以先用它初始化界面
+    pub fn status(&self) -> PlayerStatus {
+        self.status
+            .read()
+            .map(|status| status.clone())
+            .unwrap_or_default()
+    }
+
+    async fn audio_thread_main(
+        receiver: mpsc::Receiver<BackendState>,
+        output: OutputKind,
+        events: broadcast::Sender<PlayerEvent>,
+        status: Arc<RwLock<PlayerStatus>>,
+    ) -> Result<(), AppError> {
+        let (mut output, fallback) = Self::open_output(&output)?;
+        let mut decks = Decks::new(&output)?;
+        let mut player = PlayerState {
+            queue: get_queue_store().load().unwrap_or_else(|| {
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or_default();
+                Queue::with_seed(seed)
+            }),
+            crossfade: crossfade_config(),
+            normalization: get_settings().get_or_default(NORMALIZATION_SETTING),
+            dsp: Arc::new(DspControl::new(dsp_settings())),
+            output: output.name().to_string(),
+            events: Some(events),
+            status,
+            ..Default::default()
+        };
+        if fallback {
+            player.emit(PlayerEvent::OutputChanged {
+                device: player.output.clone(),
+                fallback: true,
+            });
+        }
+
+        loop {
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(command) => {
+                    let handled =
+                        Self::handle_command(&mut output, &mut decks, &mut player, command).await;
+                    if let Err(e) = handled {
+                        error!("Failed to handle audio command: {}", e);
+                        player.emit(PlayerEvent::Error(e.to_string()));
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    player.save_progress(decks.main());
+                    player.end_track(EndReason::Interrupted);
+                    break;
+                }
+            }
 
-        while let Ok(command) = receiver.recv() {
-            if let Err(e) = Self::handle_command(&sink, command).await {
-                error!("Failed to handle audio command: {}", e);
+            if let Err(e) = Self::advance(&decks, &mut player).await {
+                error!("Failed to advance playback: {}", e);
+                player.emit(PlayerEvent::Error(e.to_string()));
             }
+            if output.lost() {
+                Self::recover_output(&mut output, &mut decks, &mut player);
+            }
+            player.sync_queue();
+            player.publish(decks.main());
         }
 
-        drop(sink);
+        drop(decks);
+        drop(output);
         Ok(())
     }
 
-    async fn handle_command(sink: &Sink, command: BackendState) -> Result<(), AppError> {
-        match command {
-            BackendState::Set(target) => {
-                sink.clear();
-
-                let reader = crate::audio::reader::Reader::new(target.to_string());
-                if let Err(e) = reader.wait_preload(3) {
-                    return Err(AppError::Audio(format!(
-                        "Failed to preload audio data: {}",
-                        e
-                    )));
+    /// 打开保存的输出设备，不可用时依次改用默认设备与实时的无声输出，
+    /// 播放进度与队列照常推进；第二项表示是否改用了其他设备
+    fn open_output(kind: &OutputKind) -> Result<(Output, bool), AppError> {
+        let mut error = match Output::open(kind) {
+            Ok(output) => return Ok((output, false)),
+            Err(e) => e,
+        };
+        warn!("Failed to open audio output {:?}: {}", kind, error);
+        let fallbacks = [OutputKind::Device(None), OutputKind::Null { speed: 1 }];
+        for fallback in fallbacks.iter().filter(|fallback| *fallback != kind) {
+            match Output::open(fallback) {
+                Ok(output) => return Ok((output, true)),
+                Err(e) => {
+                    warn!("Failed to open audio output {:?}: {}", fallback, e);
+                    error = e;
                 }
+            }
+        }
+        Err(error)
+    }
 
-                sink.append(Decoder::new(reader.clone())?);
-                sink.play();
+    async fn handle_command(
+        output: &mut Output,
+        decks: &mut Decks,
+        player: &mut PlayerState,
+        command: BackendState,
+    ) -> Result<(), AppError> {
+        let sink = decks.main();
+        player.tick(sink);
+        match command {
+            BackendState::Set(target) => {
+                player.save_progress(sink);
+                player.end_track(EndReason::Skipped);
+                player.source = None;
+                player.origin = PlayOrigin::Unknown;
+                player.active = false;
+                player.duration = None;
+                player.pending_seek = None;
+                player.set_state(PlaybackState::Loading);
+                Self::play_url(decks, player, &target, None)?;
                 // pass
             }
             BackendState::Play(resume) => {
-                if resume {
-                    sink.play();
+                // 恢复上次保存的队列时还没有加载音频
+                if resume && sink.empty() && !player.active && player.queue.current().is_some() {
+                    Self::play_current(decks, player).await?;
+                } else if resume {
+                    decks.play();
                 } else {
-                    sink.pause();
+                    decks.pause();
                 }
                 // pass
             }
             BackendState::Speed(speed) => {
-                sink.set_speed(speed);
+                decks.set_speed(speed);
                 // pass
             }
             BackendState::Volume(volume) => {
-                sink.set_volume(volume);
+                decks.set_volume(volume);
                 // pass
             }
-            BackendState::Seek(pos) => {
-                sink.try_seek(std::time::Duration::from_secs(pos))?;
-                debug!("Seeked to position {}s", pos);
-                // pass
+            BackendState::Seek(ms) => {
+                Self::seek(decks, player, Duration::from_millis(ms))?;
+            }
+            BackendState::SeekBy(offset) => {
+                let base = player
+                    .pending_seek
+                    .map_or_else(|| sink.get_pos(), |(_, position)| position);
+                let delta = Duration::from_millis(offset.unsigned_abs());
+                let target = if offset < 0 {
+                    base.saturating_sub(delta)
+                } else {
+                    base + delta
+                };
+                Self::seek(decks, player, target)?;
+            }
+            BackendState::Replace(songs, origin, start) => {
+                player.queue.replace(songs, start);
+                player.origin = origin;
+                player.source = None;
+                player.pending_seek = None;
+                Self::play_current(decks, player).await?;
+            }
+            BackendState::Append(songs) => {
+                player.queue.append(songs);
+                if !player.active {
+                    Self::play_next(decks, player, false).await?;
+                }
+            }
+            BackendState::PlayNext(songs) => {
+                player.queue.insert_next(songs);
+                if !player.active {
+                    Self::play_next(decks, player, false).await?;
+                }
+            }
+            BackendState::Source(source) => {
+                player.queue.clear();
+                player.last_refill_error = None;
+                player.pending_seek = None;
+                if let Some(source) = &source {
+                    player.origin = source.into();
+                }
+                player.source = source.map(SourceCursor::new);
+                Self::play_next(decks, player, false).await?;
+            }
+            BackendState::Next => {
+                Self::play_next(decks, player, false).await?;
+            }
+            BackendState::Previous => {
+                if player.queue.previous().is_some() {
+                    Self::play_current(decks, player).await?;
+                }
+            }
+            BackendState::Remove(index) => {
+                if player.queue.remove(index) && player.active {
+                    if player.queue.current().is_some() {
+                        Self::play_current(decks, player).await?;
+                    } else {
+                        Self::stop(decks, player);
+                    }
+                }
+            }
+            BackendState::Move(from, to) => {
+                player.queue.move_item(from, to);
+            }
+            BackendState::ClearQueue => {
+                player.queue.clear();
+                player.source = None;
+                Self::stop(decks, player);
+            }
+            BackendState::Jump(index) => {
+                if player.queue.jump(index).is_some() {
+                    Self::play_current(decks, player).await?;
+                }
+            }
+            BackendState::Mode(mode) => {
+                player.queue.set_mode(mode);
+            }
+            BackendState::Crossfade(config) => {
+                get_settings().set(CROSSFADE_SETTING, &config);
+                player.crossfade = config;
+                // 已经准备好的下一首按旧设置衔接，重新准备
+                player.cancel_preload();
+            }
+            BackendState::Normalization(config) => {
+                get_settings().set(NORMALIZATION_SETTING, &config);
+                player.normalization = config;
+                if let Some(gain) = &player.gain {
+                    gain.set(Self::gain_factor(player.replay_gain, &config));
+                }
+                if let Some(preloaded) = &player.preloaded {
+                    preloaded
+                        .gain
+                        .set(Self::gain_factor(preloaded.replay_gain, &config));
+                }
+            }
+            BackendState::Dsp(settings) => {
+                get_settings().set(DSP_SETTING, &settings);
+                player.dsp.set(settings);
+            }
+            BackendState::Output(kind) => {
+                Self::switch_output(output, decks, player, &kind, false)?;
+                get_settings().set(OUTPUT_SETTING, &kind);
+            }
+            BackendState::Suspend(true) => {
+                if player.suspended == 0 {
+                    player.resume_after_suspend = !sink.is_paused();
+                    decks.pause();
+                }
+                player.suspended += 1;
+            }
+            BackendState::Suspend(false) => {
+                player.suspended = player.suspended.saturating_sub(1);
+                if player.suspended == 0 && player.resume_after_suspend {
+                    player.resume_after_suspend = false;
+                    decks.play();
+                }
             }
         }
+        player.check_preload();
         Ok(())
     }
 
-    pub fn dummy() -> Self {
-        let (command_sender, _) = mpsc::channel();
-        Self { command_sender }
+    /// 跳到指定位置，音频还没有加载时记下位置，加载后再跳转
+    fn seek(decks: &Decks, player: &mut PlayerState, position: Duration) -> Result<(), AppError> {
+        let position = match player.duration {
+            Some(duration) => position.min(duration),
+            None => position,
+        };
+        let sink = decks.main();
+        if sink.empty() {
+            // 只为队列中等待加载的歌曲保留，没有目标歌曲时丢弃
+            match player.queue.current() {
+                Some(song) => {
+                    debug!("Queued seek to {:?} for {}", position, song.id);
+                    player.pending_seek = Some((song.id, position));
+                }
+                None => debug!("Dropped seek to {:?} with nothing to play", position),
+            }
+            return Ok(());
+        }
+
+        player.pending_seek = None;
+        sink.try_seek(position)?;
+        debug!("Seeked to {:?}", position);
+        Ok(())
+    }
+
+    /// 切换输出设备，当前曲目重新解码后从原来的位置继续播放
+    fn switch_output(
+        output: &mut Output,
+        decks: &mut Decks,
+        player: &mut PlayerState,
+        kind: &OutputKind,
+        fallback: bool,
+    ) -> Result<(), AppError> {
+        let opened = Output::open(kind)?;
+        let sink = decks.main();
+        let position = sink.get_pos();
+        let paused = sink.is_paused();
+        let (volume, speed) = (sink.volume(), sink.speed());
+        let resume = !sink.empty();
+
+        player.cancel_preload();
+        decks.clear();
+        // 旧音源与新音源共用 Reader 的读取位置，必须先释放旧的输出
+        *decks = Decks::new(&opened)?;
+        *output = opened;
+        decks.set_volume(volume);
+        decks.set_speed(speed);
+        decks.pause();
+
+        player.fade = None;
+        player.gain = None;
+        if resume {
+            Self::reattach(decks, player, position)?;
+        }
+        if !paused {
+            decks.play();
+        }
+        info!("Switched audio output to {}", output.name());
+        player.output = output.name().to_string();
+        player.emit(PlayerEvent::OutputChanged {
+            device: player.output.clone(),
+            fallback,
+        });
+        Ok(())
+    }
+
+    /// 输出设备失效后切到默认设备，默认设备也不可用时改用无声输出
+    fn recover_output(output: &mut Output, decks: &mut Decks, player: &mut PlayerState) {
+        warn!("Audio output {} is no longer available", output.name());
+        let fallbacks = [OutputKind::Device(None), OutputKind::Null { speed: 1 }];
+        for kind in &fallbacks {
+            match Self::switch_output(output, decks, player, kind, true) {
+                Ok(()) => return,
+                Err(e) => error!("Failed to switch audio output to {:?}: {}", kind, e),
+            }
+        }
+    }
+
+    /// 在新的 Sink 中重新解码当前曲目并跳到原来的位置
+    fn reattach(
+        decks: &Decks,
+        player: &mut PlayerState,
+        position: Duration,
+    ) -> Result<(), AppError> {
+        let Some(reader) = player.reader.clone() else {
+            return Ok(());
+        };
+        reader.clone().seek(SeekFrom::Start(0))?;
+        let decoder = StreamDecoder::new(reader)?;
+        player.stream_error = Some(decoder.error());
+        let (source, gain) =
+            Self::normalize(player, decoder, player.replay_gain, None, player.duration);
+        let control = Arc::new(FadeControl::default());
+        let sink = decks.main();
+        sink.append(Fade::new(
+            source,
+            control.clone(),
+            Duration::ZERO,
+            player.crossfade.curve,
+        ));
+        sink.try_seek(position)?;
+        player.fade = Some(control);
+        player.gain = Some(gain);
+        Ok(())
+    }
+
+    /// 打开音频地址并预读开头的数据，同时读取文件中的增益标签
+    fn open(url: &str) -> Result<(Reader, StreamDecoder, Option<ReplayGain>), AppError> {
+        let reader = Reader::new(url.to_string());
+        if let Err(e) = reader.wait_preload(3) {
+            return Err(AppError::Audio(format!(
+                "Failed to preload audio data: {}",
+                e
+            )));
+        }
+
+        let tags = read_replay_gain(&mut reader.clone());
+        let decoder = StreamDecoder::new(reader.clone())?;
+        Ok((reader, decoder, tags))
+    }
+
+    /// 依次使用文件标签、网易云返回的增益与本地测量结果
+    fn resolve_gain(
+        tags: Option<ReplayGain>,
+        track: Option<(&Song, &SongUrl)>,
+    ) -> Option<ReplayGain> {
+        if tags.is_some() {
+            return tags;
+        }
+        let (song, url) = track?;
+        if let Some(gain) = url.replay_gain() {
+            return Some(gain);
+        }
+
+        let store = get_loudness_store();
+        let loudness = store.get(song.id)?;
+        Some(ReplayGain::from_loudness(
+            loudness,
+            store.album(song.album.id),
+        ))
+    }
+
+    fn gain_factor(replay_gain: Option<ReplayGain>, config: &NormalizationConfig) -> f32 {
+        replay_gain.map_or(1.0, |gain| gain.factor(config))
+    }
+
+    /// 套上归一化增益与音效，没有任何增益数据的歌曲在播放时测量响度
+    fn normalize(
+        player: &PlayerState,
+        decoder: StreamDecoder,
+        replay_gain: Option<ReplayGain>,
+        song: Option<&Song>,
+        duration: Option<Duration>,
+    ) -> (TrackSource, Arc<GainControl>) {
+        let gain = Arc::new(GainControl::new(Self::gain_factor(
+            replay_gain,
+            &player.normalization,
+        )));
+        let source = Normalize::new(decoder, gain.clone());
+        let source = match song {
+            Some(song) if replay_gain.is_none() => source.measure(song.id, song.album.id, duration),
+            _ => source,
+        };
+        (Dsp::new(source, player.dsp.clone()), gain)
+    }
+
+    fn play_url(
+        decks: &Decks,
+        player: &mut PlayerState,
+        url: &str,
+        track: Option<(&Song, &SongUrl)>,
+    ) -> Result<(), AppError> {
+        player.cancel_preload();
+        decks.clear();
+        player.reader = None;
+        player.stream_error = None;
+        player.fade = None;
+        player.gain = None;
+        player.total_bytes = track.map(|(_, url)| url.size).filter(|size| *size > 0);
+        player.last_buffered = 0;
+
+        let (reader, decoder, tags) = Self::open(url)?;
+        if player.duration.is_none() {
+            player.duration = decoder.total_duration();
+        }
+        let replay_gain = Self::resolve_gain(tags, track);
+        let error = decoder.error();
+        let (source, gain) = Self::normalize(
+            player,
+            decoder,
+            replay_gain,
+            track.map(|(song, _)| song),
+            player.duration,
+        );
+        let control = Arc::new(FadeControl::default());
+        let sink = decks.main();
+        sink.append(Fade::new(
+            source,
+            control.clone(),
+            Duration::ZERO,
+            player.crossfade.curve,
+        ));
+        sink.play();
+        player.reader = Some(reader);
+        player.stream_error = Some(error);
+        player.fade = Some(control);
+        player.gain = Some(gain);
+        player.replay_gain = replay_gain;
+        Ok(())
+    }
+
+    /// 当前曲目播放结束后切到下一首，并在待播歌曲不足时向来源补充
+    async fn advance(decks: &Decks, player: &mut PlayerState) -> Result<(), AppError> {
+        let sink = decks.main();
+        player.tick(sink);
+
+        // 预加载的曲目已经接着播放，或到了开始淡入淡出的位置
+        Self::take_preloaded(decks, player);
+
+        let ended = decks.main().empty();
+        let finished = player.active && ended;
+        if finished {
+            player.finish_track();
+        } else if ended {
+            // 直接播放的地址没有曲目记录，只上报错误
+            player.report_stream_error();
+        }
+
+        if player.source.is_some() && player.queue.remaining() < REFILL_THRESHOLD {
+            Self::refill(player).await;
+        }
+
+        if finished {
+            Self::play_next(decks, player, true).await?;
+        } else {
+            player.check_preload();
+            Self::preload(decks, player);
+        }
+        Ok(())
+    }
+
+    /// 当前曲目剩余的时长，总时长未知时为 `None`
+    fn remaining_time(sink: &Sink, duration: Option<Duration>) -> Option<Duration> {
+        Some(duration?.saturating_sub(sink.get_pos()))
+    }
+
+    /// 在当前曲目快结束时准备下一首，打开音频流在后台进行，这里只收取结果
+    fn preload(decks: &Decks, player: &mut PlayerState) {
+        if !player.active || player.preloaded.is_some() {
+            return;
+        }
+        if let Some(preloading) = &mut player.preloading {
+            let result = match preloading.result.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Closed) => {
+                    Err(AppError::Thread("Preload task stopped".to_string()))
+                }
+            };
+            let Some(preloading) = player.preloading.take() else {
+                return;
+            };
+            match result {
+                Ok(opened) => Self::attach_preloaded(decks, player, preloading.song, opened),
+                Err(e) => {
+                    warn!("Failed to preload {}: {}", preloading.song.id, e);
+                    player.preload_failed = Some(preloading.song.id);
+                }
+            }
+            return;
+        }
+
+        let crossfade = player.crossfade.duration();
+        let Some(remaining) = Self::remaining_time(decks.main(), player.duration) else {
+            return;
+        };
+        if remaining > PRELOAD_AHEAD + crossfade {
+            return;
+        }
+        let Some(song) = player.queue.upcoming().cloned() else {
+            return;
+        };
+        if player.preload_failed == Some(song.id) {
+            return;
+        }
+        player.preloading = Some(Self::start_preload(song));
+    }
+
+    /// 解析地址并等待首批数据，等待可能长达数十秒，不能占用播放线程
+    fn start_preload(song: Song) -> Preloading {
+        let (sender, result) = oneshot::channel();
+        let id = song.id;
+        let task = tokio::spawn(async move {
+            let opened: Result<Opened, AppError> = async {
+                let url = resolve_url(id).await?;
+                // 无法播放的歌曲留给切歌时跳过
+                let address = url
+                    .url
+                    .clone()
+                    .ok_or_else(|| AppError::Audio(format!("No playable url for {}", id)))?;
+                let (reader, decoder, tags) =
+                    tokio::task::spawn_blocking(move || Self::open(&address))
+                        .await
+                        .map_err(|e| AppError::Thread(e.to_string()))??;
+                Ok(Opened {
+                    url,
+                    reader,
+                    decoder,
+                    tags,
+                })
+            }
+            .await;
+            let _ = sender.send(opened);
+        });
+        Preloading {
+            song,
+            result,
+            task: task.abort_handle(),
+        }
+    }
+
+    /// 同一专辑的连续曲目或未开启淡入淡出时直接排到当前曲目之后，实现无缝播放
+    fn attach_preloaded(decks: &Decks, player: &mut PlayerState, song: Song, opened: Opened) {
+        let Opened {
+            url,
+            reader,
+            decoder,
+            tags,
+        } = opened;
+        let same_album = player
+            .current
+            .as_ref()
+            .is_some_and(|c| c.song.album.id != 0 && c.song.album.id == song.album.id);
+        let crossfade = if same_album {
+            Duration::ZERO
+        } else {
+            player.crossfade.duration()
+        };
+        let duration = Some(Duration::from_millis(song.duration))
+            .filter(|d| !d.is_zero())
+            .or_else(|| decoder.total_duration());
+        let replay_gain = Self::resolve_gain(tags, Some((&song, &url)));
+        let error = decoder.error();
+        let (source, gain) = Self::normalize(player, decoder, replay_gain, Some(&song), duration);
+        let control = Arc::new(FadeControl::default());
+        let pending = if crossfade.is_zero() {
+            decks.main().append(Fade::new(
+                source,
+                control.clone(),
+                Duration::ZERO,
+                player.crossfade.curve,
+            ));
+            None
+        } else {
+            Some(source)
+        };
+
+        debug!(
+            "Preloaded {} - {} ({:?} crossfade)",
+            song.name,
+            song.artist_names(),
+            crossfade
+        );
+        player.preloaded = Some(Preloaded {
+            song,
+            reader,
+            size: Some(url.size).filter(|size| *size > 0),
+            duration,
+            control,
+            gain,
+            replay_gain,
+            error,
+            pending,
+            crossfade,
+        });
+    }
+
+    /// 到了衔接的时机时切换到预加载的曲目
+    fn take_preloaded(decks: &Decks, player: &mut PlayerState) {
+        let sink = decks.main();
+        let remaining = Self::remaining_time(sink, player.duration);
+        let Some(preloaded) = &mut player.preloaded else {
+            return;
+        };
+
+        if let Some(source) = preloaded.pending.take() {
+            let due = remaining.is_some_and(|r| r <= preloaded.crossfade);
+            if !due {
+                preloaded.pending = Some(source);
+                return;
+            }
+
+            // 旧曲目在原来的 Sink 中淡出，新曲目在另一个 Sink 中淡入
+            if let Some(fade) = &player.fade {
+                fade.fade_out(preloaded.crossfade, player.crossfade.curve);
+            }
+            let other = decks.other();
+            other.clear();
+            other.append(Fade::new(
+                source,
+                preloaded.control.clone(),
+                preloaded.crossfade,
+                player.crossfade.curve,
+            ));
+            if !sink.is_paused() {
+                other.play();
+            }
+            decks.swap();
+        } else if !preloaded.control.started() {
+            return;
+        }
+
+        let Some(preloaded) = player.preloaded.take() else {
+            return;
+        };
+        // 无缝衔接时出错的曲目同样会直接接上下一首
+        player.finish_track();
+        if player
+            .queue
+            .next(true)
+            .is_none_or(|song| song.id != preloaded.song.id)
+        {
+            warn!(
+                "Queue moved away from preloaded track {}",
+                preloaded.song.id
+            );
+        }
+
+        debug!(
+            "Now playing {} - {}",
+            preloaded.song.name,
+            preloaded.song.artist_names()
+        );
+        player.reader = Some(preloaded.reader);
+        player.stream_error = Some(preloaded.error);
+        player.fade = Some(preloaded.control);
+        player.gain = Some(preloaded.gain);
+        player.replay_gain = preloaded.replay_gain;
+        player.total_bytes = preloaded.size;
+        player.duration = preloaded.duration;
+        player.last_buffered = 0;
+        player.start_track(preloaded.song);
+        player.restore_progress(decks.main());
+    }
+
+    async fn refill(player: &mut PlayerState) {
+        if player
+            .last_refill_error
+            .is_some_and(|at| at.elapsed() < REFILL_RETRY)
+        {
+            return;
+        }
+        let Some(cursor) = player.source.as_mut() else {
+            return;
+        };
+
+        match cursor.next_batch().await {
+            Ok(songs) => {
+                player.last_refill_error = None;
+                player.queue.append(songs);
+            }
+            Err(e) => {
+                warn!("Failed to fetch tracks from {:?}: {}", cursor.source(), e);
+                player.last_refill_error = Some(Instant::now());
+            }
+        }
+    }
+
+    /// 每日推荐播完后以最后一首歌开启歌曲电台继续播放
+    fn fallback_to_radio(player: &mut PlayerState) {
+        if player.source.is_some() || player.origin != PlayOrigin::DailyRecommend {
+            return;
+        }
+        let Some(song_id) = player.last_played else {
+            return;
+        };
+
+        debug!(
+            "Daily recommendations finished, starting song radio from {}",
+            song_id
+        );
+        let source = QueueSource::SongRadio { song_id };
+        player.origin = (&source).into();
+        player.source = Some(SourceCursor::new(source));
+    }
+
+    /// 切到下一首，`auto` 表示上一首自然播放结束
+    async fn play_next(
+        decks: &Decks,
+        player: &mut PlayerState,
+        auto: bool,
+    ) -> Result<(), AppError> {
+        if player.queue.remaining() == 0 {
+            Self::fallback_to_radio(player);
+            Self::refill(player).await;
+        }
+
+        if player.queue.next(auto).is_some() {
+            Self::play_current(decks, player).await
+        } else {
+            Self::stop(decks, player);
+            Ok(())
+        }
+    }
+
+    /// 播放队列的当前歌曲，无法获取播放地址时继续向后跳过
+    async fn play_current(decks: &Decks, player: &mut PlayerState) -> Result<(), AppError> {
+        // 自然结束的曲目已在 advance 中上报，这里剩下的都是被切掉的
+        player.save_progress(decks.main());
+        player.end_track(EndReason::Skipped);
+
+        for _ in 0..player.queue.len() {
+            let Some(song) = player.queue.current().cloned() else {
+                break;
+            };
+            player.set_state(PlaybackState::Loading);
+            let song_url = match resolve_url(song.id).await {
+                Ok(url) => Some(url),
+                Err(e) => {
+                    warn!("Failed to resolve url of {}: {}", song.id, e);
+                    player.emit(PlayerEvent::Error(e.to_string()));
+                    None
+                }
+            };
+            let Some((song_url, url)) =
+                song_url.and_then(|song_url| song_url.url.clone().map(|url| (song_url, url)))
+            else {
+                warn!("Song {} ({}) is unavailable, skipping", song.id, song.name);
+                if player.queue.next(false).is_none() {
+                    break;
+                }
+                continue;
+            };
+
+            debug!("Now playing {} - {}", song.name, song.artist_names());
+            player.active = true;
+            player.duration = Some(Duration::from_millis(song.duration)).filter(|d| !d.is_zero());
+            Self::play_url(decks, player, &url, Some((&song, &song_url)))?;
+            player.start_track(song);
+            player.restore_progress(decks.main());
+            return Ok(());
+        }
+
+        Self::stop(decks, player);
+        Ok(())
+    }
+
+    fn stop(decks: &Decks, player: &mut PlayerState) {
+        player.save_progress(decks.main());
+        player.end_track(EndReason::Skipped);
+        player.cancel_preload();
+        decks.clear();
+        player.active = false;
+        player.reader = None;
+        player.stream_error = None;
+        player.fade = None;
+        player.gain = None;
+        player.replay_gain = None;
+        player.duration = None;
+        player.total_bytes = None;
+        player.pending_seek = None;
+        player.sync_state(decks.main());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::download::CHUNK_SIZE;
+    use crate::models::audio::FadeCurve;
+    use crate::testing::{self, FLAC_BLOCK, MockServer};
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    /// 等到满足条件的事件，返回期间收到的全部事件
+    fn wait_for(
+        events: &mut broadcast::Receiver<PlayerEvent>,
+        mut done: impl FnMut(&PlayerEvent) -> bool,
+    ) -> Vec<PlayerEvent> {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        let mut seen = Vec::new();
+        loop {
+            match events.try_recv() {
+                Ok(event) => {
+                    let matched = done(&event);
+                    seen.push(event);
+                    if matched {
+                        return seen;
+                    }
+                }
+                Err(TryRecvError::Empty) => {
+                    assert!(Instant::now() < deadline, "timed out after {:?}", seen);
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => panic!("event channel failed: {}", e),
+            }
+        }
+    }
+
+    fn is_state(state: PlaybackState) -> impl Fn(&PlayerEvent) -> bool {
+        move |event| matches!(event, PlayerEvent::StateChanged(s) if *s == state)
+    }
+
+    #[test]
+    fn missing_device_falls_back() {
+        let missing = OutputKind::Device(Some("cloubit-missing-device".to_string()));
+        let (output, fallback) = AudioBackend::open_output(&missing).unwrap();
+        assert!(fallback);
+        assert_ne!(output.name(), "cloubit-missing-device");
+
+        let (output, fallback) = AudioBackend::open_output(&OutputKind::Null { speed: 1 }).unwrap();
+        assert!(!fallback);
+        assert_eq!(output.name(), "null");
+    }
+
+    #[test]
+    fn null_output_follows_commands() {
+        let frames = 108;
+        let server = MockServer::file(testing::flac(frames));
+        let backend = AudioBackend::with_output(OutputKind::Null { speed: 2 }).unwrap();
+        let mut events = backend.subscribe();
+        let send = |command| backend.command_sender.send(command).unwrap();
+
+        send(BackendState::Set(server.url().to_string()));
+        let seen = wait_for(&mut events, is_state(PlaybackState::Playing));
+        assert!(seen.iter().any(is_state(PlaybackState::Loading)));
+        let expected = Duration::from_secs_f64((frames * FLAC_BLOCK) as f64 / 44100.0);
+        let duration = backend.status().duration.unwrap();
+        assert!(duration.abs_diff(expected) < Duration::from_millis(1));
+
+        send(BackendState::Play(false));
+        wait_for(&mut events, is_state(PlaybackState::Paused));
+        send(BackendState::Seek(6000));
+        send(BackendState::Play(true));
+        wait_for(&mut events, is_state(PlaybackState::Playing));
+        let seen = wait_for(&mut events, |event| {
+            matches!(event, PlayerEvent::Position { .. })
+        });
+        let Some(PlayerEvent::Position { position, .. }) = seen.last() else {
+            unreachable!();
+        };
+        assert!(*position >= Duration::from_secs(6), "{:?}", position);
+        assert!(*position < Duration::from_secs(8), "{:?}", position);
+
+        wait_for(&mut events, is_state(PlaybackState::Stopped));
+        assert_eq!(backend.status().position, Duration::ZERO);
+    }
+
+    fn wait_status(backend: &AudioBackend, done: impl Fn(&PlayerStatus) -> bool) -> PlayerStatus {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        loop {
+            let status = backend.status();
+            if done(&status) {
+                return status;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "timed out at {:?}",
+                status.position
+            );
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn seek_by_clamps_to_track() {
+        let server = MockServer::file(testing::flac(54));
+        let backend = AudioBackend::with_output(OutputKind::Null { speed: 1 }).unwrap();
+        let mut events = backend.subscribe();
+        let send = |command| backend.command_sender.send(command).unwrap();
+
+        send(BackendState::Set(server.url().to_string()));
+        wait_for(&mut events, is_state(PlaybackState::Playing));
+        send(BackendState::Play(false));
+        wait_for(&mut events, is_state(PlaybackState::Paused));
+
+        let at = |ms: u64| {
+            let target = Duration::from_millis(ms);
+            move |status: &PlayerStatus| {
+                status.position.abs_diff(target) < Duration::from_millis(50)
+            }
+        };
+        send(BackendState::SeekBy(-60_000));
+        wait_status(&backend, at(0));
+        send(BackendState::SeekBy(2_000));
+        wait_status(&backend, at(2_000));
+        send(BackendState::SeekBy(-500));
+        wait_status(&backend, at(1_500));
+        send(BackendState::SeekBy(60_000));
+        let status = wait_status(&backend, |status| status.position > Duration::from_secs(4));
+        assert!(status.position <= status.duration.unwrap());
+
+        send(BackendState::Play(true));
+        wait_for(&mut events, is_state(PlaybackState::Stopped));
+    }
+
+    #[test]
+    fn queued_seek_is_dropped_for_new_url() {
+        let server = MockServer::file(testing::flac(108));
+        let backend = AudioBackend::with_output(OutputKind::Null { speed: 1 }).unwrap();
+        let mut events = backend.subscribe();
+
+        // 没有加载音频时的跳转不能作用到之后打开的地址上
+        backend
+            .command_sender
+            .send(BackendState::Seek(6_000))
+            .unwrap();
+        backend
+            .command_sender
+            .send(BackendState::Set(server.url().to_string()))
+            .unwrap();
+        let seen = wait_for(&mut events, |event| {
+            matches!(event, PlayerEvent::Position { .. })
+        });
+        let Some(PlayerEvent::Position { position, .. }) = seen.last() else {
+            unreachable!();
+        };
+        assert!(*position < Duration::from_secs(2), "{:?}", position);
+    }
+
+    #[test]
+    fn stream_failure_emits_error() {
+        // 前两块可以下载，之后断网
+        let data = testing::flac(100);
+        let server = MockServer::failing_file(data, CHUNK_SIZE * 2);
+        let backend = AudioBackend::with_output(OutputKind::Null { speed: 10 }).unwrap();
+        let mut events = backend.subscribe();
+
+        backend
+            .command_sender
+            .send(BackendState::Set(server.url().to_string()))
+            .unwrap();
+        let seen = wait_for(&mut events, |event| matches!(event, PlayerEvent::Error(_)));
+        assert!(seen.iter().any(is_state(PlaybackState::Playing)));
+        wait_for(&mut events, is_state(PlaybackState::Stopped));
+    }
+
+    /// 注册到测试服务器上的歌曲，时长按 [`testing::flac`] 的帧数计算
+    fn queued_song(id: u64, server: &MockServer, frames: u64, album: u64) -> Song {
+        testing::register_song(id, server.url());
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "dt": frames * FLAC_BLOCK * 1000 / 44100,
+            "al": { "id": album },
+        }))
+        .unwrap()
+    }
+
+    fn track_events(seen: &[PlayerEvent]) -> Vec<(u64, Option<EndReason>)> {
+        seen.iter()
+            .filter_map(|event| match event {
+                PlayerEvent::TrackStarted { song, .. } => Some((song.id, None)),
+                PlayerEvent::TrackEnded { song, reason, .. } => Some((song.id, Some(*reason))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn queue_advances_gapless_and_crossfaded() {
+        let first = MockServer::file(testing::flac(33));
+        let second = MockServer::file(testing::flac(11));
+        let songs = vec![
+            queued_song(46_001, &first, 33, 46_001),
+            queued_song(46_002, &second, 11, 46_002),
+        ];
+        let expected = [
+            (46_001, None),
+            (46_001, Some(EndReason::Finished)),
+            (46_002, None),
+            (46_002, Some(EndReason::Finished)),
+        ];
+        let backend = AudioBackend::with_output(OutputKind::Null { speed: 1 }).unwrap();
+        let mut events = backend.subscribe();
+        let send = |command| backend.command_sender.send(command).unwrap();
+        let play_queue = |events: &mut broadcast::Receiver<PlayerEvent>| {
+            send(BackendState::Replace(
+                songs.clone(),
+                PlayOrigin::Playlist(46),
+                0,
+            ));
+            wait_for(
+                events,
+                |event| matches!(event, PlayerEvent::TrackEnded { song, .. } if song.id == 46_002),
+            )
+        };
+        let first_listened = |seen: &[PlayerEvent]| {
+            seen.iter()
+                .find_map(|event| match event {
+                    PlayerEvent::TrackEnded { song, listened, .. } if song.id == 46_001 => {
+                        Some(*listened)
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        };
+        let duration = Duration::from_millis(songs[0].duration);
+
+        // 默认不淡入淡出，第一首播完后无缝接上第二首
+        let seen = play_queue(&mut events);
+        assert_eq!(track_events(&seen), expected);
+        assert!(first_listened(&seen) + Duration::from_millis(600) > duration);
+
+        // 淡入淡出时第一首在结尾前就切到第二首
+        send(BackendState::Crossfade(CrossfadeConfig {
+            duration_ms: 2000,
+            curve: FadeCurve::Linear,
+        }));
+        let seen = play_queue(&mut events);
+        send(BackendState::Crossfade(CrossfadeConfig::default()));
+        assert_eq!(track_events(&seen), expected);
+        assert!(first_listened(&seen) + Duration::from_secs(1) < duration);
+    }
+
+    #[test]
+    fn failed_track_ends_interrupted() {
+        // 同一专辑的下一首已经无缝排在后面，出错的曲目结束后直接接上
+        let failing = MockServer::failing_file(testing::flac(100), CHUNK_SIZE * 2);
+        let complete = MockServer::file(testing::flac(11));
+        let songs = vec![
+            queued_song(32_001, &failing, 100, 32),
+            queued_song(32_002, &complete, 11, 32),
+        ];
+        let backend = AudioBackend::with_output(OutputKind::Null { speed: 1 }).unwrap();
+        let mut events = backend.subscribe();
+
+        backend
+            .command_sender
+            .send(BackendState::Replace(songs, PlayOrigin::Playlist(32), 0))
+            .unwrap();
+        let seen = wait_for(
+            &mut events,
+            |event| matches!(event, PlayerEvent::TrackEnded { song, .. } if song.id == 32_002),
+        );
+        assert_eq!(
+            track_events(&seen),
+            [
+                (32_001, None),
+                (32_001, Some(EndReason::Interrupted)),
+                (32_002, None),
+                (32_002, Some(EndReason::Finished)),
+            ]
+        );
+        let error = seen
+            .iter()
+            .position(|event| matches!(event, PlayerEvent::Error(_)))
+            .unwrap();
+        let ended = seen
+            .iter()
+            .position(|event| matches!(event, PlayerEvent::TrackEnded { .. }))
+            .unwrap();
+        assert!(error < ended);
+    }
+
+    #[test]
+    fn interrupted_program_keeps_progress() {
+        let failing = MockServer::failing_file(testing::flac(100), CHUNK_SIZE * 2);
+        let complete = MockServer::file(testing::flac(11));
+        let songs = vec![
+            queued_song(38_001, &failing, 100, 38),
+            queued_song(38_002, &complete, 11, 38),
+        ];
+        get_progress_store().save(38_002, Duration::from_millis(500));
+        let backend = AudioBackend::with_output(OutputKind::Null { speed: 1 }).unwrap();
+        let mut events = backend.subscribe();
+
+        backend
+            .command_sender
+            .send(BackendState::Replace(songs, PlayOrigin::DjRadio(38), 0))
+            .unwrap();
+        let seen = wait_for(
+            &mut events,
+            |event| matches!(event, PlayerEvent::TrackEnded { song, .. } if song.id == 38_002),
+        );
+        assert_eq!(
+            track_events(&seen),
+            [
+                (38_001, None),
+                (38_001, Some(EndReason::Interrupted)),
+                (38_002, None),
+                (38_002, Some(EndReason::Finished)),
+            ]
+        );
+        // 出错前播放了将近 3 秒，保存的进度不应停留在开头
+        let saved = get_progress_store().get(38_001).unwrap();
+        assert!(saved >= Duration::from_secs(1), "saved {:?}", saved);
+        assert_eq!(get_progress_store().get(38_002), None);
+    }
+
+    #[test]
+    fn wav_output_records_played_track() {
+        let frames = 11;
+        let samples = frames * FLAC_BLOCK;
+        let server = MockServer::file(testing::flac(frames));
+        let path = std::env::temp_dir().join(format!("cloubit-test-{}.wav", std::process::id()));
+        let backend = AudioBackend::with_output(OutputKind::Wav {
+            path: path.clone(),
+            speed: 10,
+        })
+        .unwrap();
+        let mut events = backend.subscribe();
+
+        backend
+            .command_sender
+            .send(BackendState::Set(server.url().to_string()))
+            .unwrap();
+        let seen = wait_for(&mut events, is_state(PlaybackState::Stopped));
+        assert!(seen.iter().any(is_state(PlaybackState::Playing)));
+
+        // 播放线程退出时写入最终的文件头，之后文件不再变化
+        drop(backend);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut last_len = 0;
+        let data = loop {
+            thread::sleep(Duration::from_millis(200));
+            let data = std::fs::read(&path).unwrap();
+            let length = u32::from_le_bytes(data[40..44].try_into().unwrap()) as usize;
+            if length + 44 == data.len() && data.len() == last_len {
+                break data;
+            }
+            last_len = data.len();
+            assert!(Instant::now() < deadline, "wav file was not finished");
+        };
+        let _ = std::fs::remove_file(&path);
+        assert!(data.len() as u64 >= 44 + samples * 8);
+
+        assert_eq!(&data[0..4], b"RIFF");
+        assert_eq!(&data[8..16], b"WAVEfmt ");
+        let field = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+        assert_eq!((field(20), field(22), field(34)), (3, 2, 32));
+        assert_eq!(u32::from_le_bytes(data[24..28].try_into().unwrap()), 44100);
+
+        let positions: Vec<u64> = data[44..]
+            .chunks_exact(8)
+            .map(|frame| {
+                let left = f32::from_le_bytes(frame[..4].try_into().unwrap());
+                let right = f32::from_le_bytes(frame[4..].try_into().unwrap());
+                testing::flac_position(left, right)
+            })
+            .collect();
+        // rodio 的空队列以单声道静音填充，新音源开头的一小段会按单声道转换，
+        // 之后的采样应当原样按顺序输出
+        let settle = 512;
+        let end = positions.iter().position(|p| *p == samples - 1).unwrap();
+        let tail = &positions[end + 1 - (samples - settle) as usize..=end];
+        assert_eq!(tail[0], settle);
+        assert!(tail.windows(2).all(|w| w[1] == w[0] + 1));
     }
 }
@@ -0,0 +1,57 @@
+use rodio::Sink;
+use std::cell::Cell;
+
+use crate::audio::output::Output;
+use crate::error::AppError;
+
+/// 交替使用的两个 Sink
+///
+/// 淡入淡出时新曲目在另一个 Sink 中开始，旧曲目留在原处直到淡出结束。
+/// 跳转、位置等只作用于当前曲目所在的主 Sink，暂停、音量与速度同时作用于两者
+pub struct Decks {
+    sinks: [Sink; 2],
+    active: Cell<usize>,
+}
+
+impl Decks {
+    pub fn new(output: &Output) -> Result<Self, AppError> {
+        Ok(Self {
+            sinks: [output.sink()?, output.sink()?],
+            active: Cell::new(0),
+        })
+    }
+
+    /// 当前曲目所在的 Sink
+    pub fn main(&self) -> &Sink {
+        &self.sinks[self.active.get()]
+    }
+
+    pub fn other(&self) -> &Sink {
+        &self.sinks[1 - self.active.get()]
+    }
+
+    pub fn swap(&self) {
+        self.active.set(1 - self.active.get());
+    }
+
+    pub fn play(&self) {
+        self.sinks.iter().for_each(Sink::play);
+    }
+
+    pub fn pause(&self) {
+        self.sinks.iter().for_each(Sink::pause);
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        self.sinks.iter().for_each(|sink| sink.set_volume(volume));
+    }
+
+    pub fn set_speed(&self, speed: f32) {
+        self.sinks.iter().for_each(|sink| sink.set_speed(speed));
+    }
+
+    /// 清空两个 Sink，结束正在淡出的曲目
+    pub fn clear(&self) {
+        self.sinks.iter().for_each(Sink::clear);
+    }
+}
@@ -0,0 +1,337 @@
+use log::debug;
+use rodio::Source;
+use rodio::source::SeekError;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use symphonia::core::audio::{Channels, SampleBuffer, SignalSpec};
+use symphonia::core::codecs::{self, CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use symphonia::core::units::Time;
+
+use crate::audio::reader::Reader;
+use crate::error::AppError;
+
+/// 连续解码失败超过该次数时结束播放
+const MAX_DECODE_ERRORS: usize = 3;
+
+/// rodio 自带的解码器不提供数据长度，FLAC 因此无法跳转，这里补上
+struct Media(Reader);
+
+impl MediaSource for Media {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        self.0.len()
+    }
+}
+
+impl Read for Media {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Seek for Media {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+/// 读取或解码失败导致数据流提前结束时记录的错误，由播放线程取出上报
+#[derive(Clone, Default)]
+pub struct StreamError(Arc<Mutex<Option<AppError>>>);
+
+impl StreamError {
+    fn set(&self, error: AppError) {
+        if let Ok(mut slot) = self.0.lock() {
+            *slot = Some(error);
+        }
+    }
+
+    /// 是否已经出错，不取走错误
+    pub fn is_set(&self) -> bool {
+        self.0.lock().is_ok_and(|slot| slot.is_some())
+    }
+
+    pub fn take(&self) -> Option<AppError> {
+        self.0.lock().ok()?.take()
+    }
+}
+
+/// 基于 symphonia 的流式解码器，跳转时逐帧定位到精确的采样
+pub struct StreamDecoder {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn codecs::Decoder>,
+    track_id: u32,
+    spec: SignalSpec,
+    total_duration: Option<Duration>,
+    samples: Option<SampleBuffer<f32>>,
+    /// 当前包中已解码的采样数与下一个输出的位置
+    len: usize,
+    cursor: usize,
+    error: StreamError,
+}
+
+impl StreamDecoder {
+    pub fn new(reader: Reader) -> Result<Self, AppError> {
+        let stream = MediaSourceStream::new(Box::new(Media(reader)), Default::default());
+        let options = FormatOptions {
+            enable_gapless: true,
+            ..Default::default()
+        };
+        let probed = symphonia::default::get_probe().format(
+            &Hint::new(),
+            stream,
+            &options,
+            &MetadataOptions::default(),
+        )?;
+        let format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or_else(|| AppError::Audio("No supported audio track".to_string()))?;
+        let params = &track.codec_params;
+        let decoder = symphonia::default::get_codecs().make(params, &DecoderOptions::default())?;
+        let total_duration = params
+            .time_base
+            .zip(params.n_frames)
+            .map(|(base, frames)| Duration::from(base.calc_time(frames)));
+        let spec = SignalSpec::new(
+            params.sample_rate.unwrap_or(44100),
+            params
+                .channels
+                .unwrap_or(Channels::FRONT_LEFT | Channels::FRONT_RIGHT),
+        );
+
+        let mut decoder = Self {
+            track_id: track.id,
+            format,
+            decoder,
+            spec,
+            total_duration,
+            samples: None,
+            len: 0,
+            cursor: 0,
+            error: StreamError::default(),
+        };
+        // 解码第一个包以确定实际的声道与采样率
+        decoder.decode_next();
+        Ok(decoder)
+    }
+
+    pub fn error(&self) -> StreamError {
+        self.error.clone()
+    }
+
+    /// 解码下一个包并返回其时间戳与时长，流结束或出错时为 `None`
+    fn decode_next(&mut self) -> Option<(u64, u64)> {
+        let mut errors = 0;
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(Error::ResetRequired) => {
+                    self.decoder.reset();
+                    continue;
+                }
+                Err(Error::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                    return None;
+                }
+                Err(e) => {
+                    debug!("Failed to read packet: {}", e);
+                    self.error.set(e.into());
+                    return None;
+                }
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            match self.decoder.decode(&packet) {
+                Ok(decoded) => {
+                    if decoded.frames() == 0 {
+                        continue;
+                    }
+                    let spec = *decoded.spec();
+                    let needed = decoded.capacity() * spec.channels.count();
+                    let samples = match &mut self.samples {
+                        Some(samples) if spec == self.spec && samples.capacity() >= needed => {
+                            samples
+                        }
+                        slot => slot.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+                    };
+                    samples.copy_interleaved_ref(decoded);
+                    self.spec = spec;
+                    self.len = samples.len();
+                    self.cursor = 0;
+                    return Some((packet.ts(), packet.dur()));
+                }
+                Err(Error::DecodeError(e)) if errors < MAX_DECODE_ERRORS => {
+                    debug!("Skipped undecodable packet: {}", e);
+                    errors += 1;
+                }
+                Err(e) => {
+                    debug!("Failed to decode packet: {}", e);
+                    self.error.set(e.into());
+                    return None;
+                }
+            }
+        }
+    }
+
+    fn channel_count(&self) -> usize {
+        self.spec.channels.count().max(1)
+    }
+}
+
+impl Iterator for StreamDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.cursor >= self.len {
+            self.decode_next()?;
+        }
+        let sample = self.samples.as_ref()?.samples().get(self.cursor).copied();
+        self.cursor += 1;
+        sample
+    }
+}
+
+impl Source for StreamDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.len.saturating_sub(self.cursor))
+    }
+
+    fn channels(&self) -> u16 {
+        self.channel_count() as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.spec.rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        // 部分格式无法跳到最后一个采样，留出一点余量
+        let pos = match self.total_duration {
+            Some(total) => pos.min(total.saturating_sub(Duration::from_millis(1))),
+            None => pos,
+        };
+        let seeked = self
+            .format
+            .seek(
+                SeekMode::Accurate,
+                SeekTo::Time {
+                    time: Time::from(pos.as_secs_f64()),
+                    track_id: Some(self.track_id),
+                },
+            )
+            .map_err(|e| SeekError::Other(Box::new(e)))?;
+        self.decoder.reset();
+        self.len = 0;
+        self.cursor = 0;
+
+        // 跳转只能落在包的开头，解码并丢弃目标之前的采样；
+        // MP3 的参考帧也在这里解码，保证目标帧的数据完整
+        while let Some((ts, dur)) = self.decode_next() {
+            if ts + dur > seeked.required_ts {
+                let frames = seeked.required_ts.saturating_sub(ts) as usize;
+                self.cursor = (frames * self.channel_count()).min(self.len);
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, FLAC_BLOCK, MP3_FRAME, MockServer};
+
+    fn open(server: &MockServer) -> StreamDecoder {
+        StreamDecoder::new(Reader::new(server.url().to_string())).unwrap()
+    }
+
+    fn sample_at(ms: u64) -> u64 {
+        ms * 44100 / 1000
+    }
+
+    fn assert_duration(decoder: &StreamDecoder, samples: u64) {
+        let expected = Duration::from_secs_f64(samples as f64 / 44100.0);
+        let duration = decoder.total_duration().unwrap();
+        assert!(duration.abs_diff(expected) < Duration::from_millis(1));
+    }
+
+    #[test]
+    fn flac_seeks_to_exact_sample() {
+        let frames = 40;
+        let server = MockServer::file(testing::flac(frames));
+        let mut decoder = open(&server);
+        assert_duration(&decoder, frames * FLAC_BLOCK);
+
+        // 向前、向后以及跳到帧边界附近
+        for ms in [1234, 90, 3700, 0, 2500, 93] {
+            decoder.try_seek(Duration::from_millis(ms)).unwrap();
+            let (left, right) = (decoder.next().unwrap(), decoder.next().unwrap());
+            let position = testing::flac_position(left, right);
+            assert!(
+                position.abs_diff(sample_at(ms)) <= 1,
+                "seek to {}ms landed on sample {}",
+                ms,
+                position
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_stream_reports_error() {
+        let data = testing::flac(40);
+        let complete = MockServer::file(data.clone());
+        let mut decoder = open(&complete);
+        assert_eq!(decoder.by_ref().count() as u64, 40 * FLAC_BLOCK * 2);
+        assert!(decoder.error().take().is_none());
+
+        // 服务器声称的长度比实际发送的数据多，读到一半数据就没有了
+        let length = data.len() + 100_000;
+        let truncated = MockServer::truncated_file(data, length);
+        let mut decoder = open(&truncated);
+        assert!((decoder.by_ref().count() as u64) < 40 * FLAC_BLOCK * 2);
+        assert!(decoder.error().take().is_some());
+    }
+
+    #[test]
+    fn vbr_mp3_seeks_within_one_frame() {
+        let bitrates: Vec<u32> = [128, 64, 320, 96].into_iter().cycle().take(120).collect();
+        let samples = bitrates.len() as u64 * MP3_FRAME;
+        let server = MockServer::file(testing::mp3(&bitrates));
+        let mut decoder = open(&server);
+        assert_eq!(decoder.channels(), 1);
+        assert_duration(&decoder, samples);
+        assert_eq!(decoder.by_ref().count() as u64, samples);
+
+        // 静音帧无法区分位置，以跳转后剩余的采样数推算
+        for ms in [1000, 250, 3000, 0, 1777] {
+            decoder.try_seek(Duration::from_millis(ms)).unwrap();
+            let remaining = decoder.by_ref().count() as u64;
+            let position = samples - remaining;
+            assert!(
+                position.abs_diff(sample_at(ms)) < MP3_FRAME,
+                "seek to {}ms landed on sample {}",
+                ms,
+                position
+            );
+        }
+    }
+}
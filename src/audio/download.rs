@@ -0,0 +1,421 @@
+use log::debug;
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_RANGE, HeaderMap, HeaderValue, RANGE};
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+use tokio::task::{AbortHandle, Id, JoinSet};
+
+use crate::error::AppError;
+
+pub const CHUNK_SIZE: usize = 256 * 1024;
+const MAX_CACHED_CHUNKS: usize = 8;
+/// 同一音频流同时进行的请求数
+const MAX_CONCURRENT: usize = 3;
+/// 跳转后继续保留的预取范围，超出的预取会被取消
+const PREFETCH_BEHIND: u64 = 1;
+const PREFETCH_AHEAD: u64 = 3;
+/// 等待数据块的最长时间，调度任务异常退出时不会一直阻塞
+const WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+static RT: OnceLock<Runtime> = OnceLock::new();
+
+fn get_rt() -> &'static Runtime {
+    RT.get_or_init(|| Runtime::new().expect("Failed to create runtime"))
+}
+
+enum Request {
+    /// 下载一个数据块，`urgent` 表示解码器正在等待
+    Fetch { index: u64, urgent: bool },
+    /// 读取位置跳转到该块，取消范围外的预取
+    Seek(u64),
+}
+
+#[derive(Default)]
+struct Cache {
+    chunks: HashMap<u64, Arc<Vec<u8>>>,
+    /// 最近一次下载失败的原因，等待方据此返回
+    failed: HashMap<u64, AppError>,
+    /// 正在被等待的块，清理缓存时保留
+    waiting: HashMap<u64, usize>,
+    /// 下载完成过的字节范围，按起点排序且互不相邻，不随缓存清理而减少
+    downloaded: Vec<Range<u64>>,
+}
+
+impl Cache {
+    fn mark_downloaded(&mut self, range: Range<u64>) {
+        let at = self
+            .downloaded
+            .partition_point(|existing| existing.end < range.start);
+        let mut merged = range;
+        while let Some(next) = self.downloaded.get(at) {
+            if next.start > merged.end {
+                break;
+            }
+            merged = merged.start.min(next.start)..merged.end.max(next.end);
+            self.downloaded.remove(at);
+        }
+        self.downloaded.insert(at, merged);
+    }
+}
+
+struct Shared {
+    url: String,
+    client: reqwest::Client,
+    cache: Mutex<Cache>,
+    ready: Condvar,
+    /// 数据总长度，从响应头或 HEAD 请求得知，0 表示未知
+    length: AtomicU64,
+    position: Arc<Mutex<u64>>,
+}
+
+impl Shared {
+    fn len(&self) -> Option<u64> {
+        Some(self.length.load(Ordering::Relaxed)).filter(|length| *length > 0)
+    }
+
+    fn is_past_end(&self, index: u64) -> bool {
+        self.len()
+            .is_some_and(|length| index * CHUNK_SIZE as u64 >= length)
+    }
+
+    fn is_cached(&self, index: u64) -> bool {
+        self.cache
+            .lock()
+            .is_ok_and(|cache| cache.chunks.contains_key(&index))
+    }
+
+    fn complete(&self, index: u64, result: Result<Vec<u8>, AppError>) {
+        let Ok(mut cache) = self.cache.lock() else {
+            return;
+        };
+        match result {
+            Ok(data) => {
+                if cache.chunks.len() >= MAX_CACHED_CHUNKS {
+                    let current = self.position.lock().map_or(0, |position| *position);
+                    evict(&mut cache, current / CHUNK_SIZE as u64);
+                }
+                let start = index * CHUNK_SIZE as u64;
+                cache.mark_downloaded(start..start + data.len() as u64);
+                cache.failed.remove(&index);
+                cache.chunks.insert(index, Arc::new(data));
+            }
+            Err(e) => {
+                debug!("Failed to fetch chunk {}: {}", index, e);
+                cache.failed.insert(index, e);
+            }
+        }
+        drop(cache);
+        self.ready.notify_all();
+    }
+}
+
+/// 只保留当前位置附近的块，正在被等待的块不会被清理
+fn evict(cache: &mut Cache, current: u64) {
+    let keep_range = current.saturating_sub(2)..=current + 6;
+    let old_len = cache.chunks.len();
+    let Cache {
+        chunks, waiting, ..
+    } = cache;
+    chunks.retain(|k, _| keep_range.contains(k) || waiting.contains_key(k));
+
+    while chunks.len() >= MAX_CACHED_CHUNKS {
+        let Some(&farthest) = chunks
+            .keys()
+            .filter(|k| !waiting.contains_key(k))
+            .max_by_key(|&&k| k.abs_diff(current))
+        else {
+            break;
+        };
+        chunks.remove(&farthest);
+    }
+
+    debug!(
+        "Cache cleanup: reduced from {} to {} data chunks",
+        old_len,
+        chunks.len()
+    );
+}
+
+/// 音频流的分块下载调度，克隆后共享同一个调度任务与缓存
+///
+/// 所有克隆都释放后调度任务结束，未完成的下载随之取消
+#[derive(Clone)]
+pub struct Downloads {
+    shared: Arc<Shared>,
+    requests: mpsc::UnboundedSender<Request>,
+}
+
+impl Downloads {
+    pub fn new(url: String, position: Arc<Mutex<u64>>) -> Self {
+        let client = reqwest::Client::builder()
+            .default_headers({
+                let mut headers = HeaderMap::new();
+                headers.insert("User-Agent", HeaderValue::from_static("AudioReader/1.0"));
+                headers.insert("Accept-Ranges", HeaderValue::from_static("bytes"));
+                headers
+            })
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .pool_max_idle_per_host(6)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        let shared = Arc::new(Shared {
+            url,
+            client,
+            cache: Mutex::new(Cache::default()),
+            ready: Condvar::new(),
+            length: AtomicU64::new(0),
+            position,
+        });
+        let (requests, receiver) = mpsc::unbounded_channel();
+        get_rt().spawn(schedule(shared.clone(), receiver));
+
+        Self { shared, requests }
+    }
+
+    /// 数据总长度，还没有收到过响应时为 `None`
+    pub fn len(&self) -> Option<u64> {
+        self.shared.len()
+    }
+
+    /// 已下载的字节范围，按起点排序并合并相邻的块
+    ///
+    /// 包括已经从缓存中清理掉的块
+    pub fn buffered_ranges(&self) -> Vec<Range<u64>> {
+        self.shared
+            .cache
+            .lock()
+            .map(|cache| cache.downloaded.clone())
+            .unwrap_or_default()
+    }
+
+    fn send(&self, request: Request) {
+        // 调度任务只会在所有句柄释放后退出，发送失败时没有人在等待结果
+        let _ = self.requests.send(request);
+    }
+
+    /// 在后台预取这些块，不阻塞调用方
+    pub fn prefetch(&self, chunks: Range<u64>) {
+        for index in chunks {
+            self.send(Request::Fetch {
+                index,
+                urgent: false,
+            });
+        }
+    }
+
+    /// 读取位置跳转后调用，取消不再需要的预取并预取新位置附近的块
+    pub fn seek(&self, index: u64) {
+        self.send(Request::Seek(index));
+    }
+
+    /// 取得一个数据块，没有缓存时优先下载并阻塞等待
+    pub fn get(&self, index: u64) -> Result<Arc<Vec<u8>>, AppError> {
+        let mut cache = self.shared.cache.lock()?;
+        if let Some(chunk) = cache.chunks.get(&index) {
+            return Ok(Arc::clone(chunk));
+        }
+        cache.failed.remove(&index);
+        *cache.waiting.entry(index).or_default() += 1;
+        self.send(Request::Fetch {
+            index,
+            urgent: true,
+        });
+
+        let deadline = Instant::now() + WAIT_TIMEOUT;
+        let result = loop {
+            if let Some(chunk) = cache.chunks.get(&index) {
+                break Ok(Arc::clone(chunk));
+            }
+            if let Some(e) = cache.failed.get(&index) {
+                break Err(e.clone());
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break Err(AppError::Network(format!(
+                    "Timed out waiting for chunk {}",
+                    index
+                )));
+            }
+            cache = self.shared.ready.wait_timeout(cache, remaining)?.0;
+        };
+
+        if let Some(count) = cache.waiting.get_mut(&index) {
+            *count -= 1;
+            if *count == 0 {
+                cache.waiting.remove(&index);
+            }
+        }
+        result
+    }
+
+    /// 服务器没有在范围响应中给出总长度时，用 HEAD 请求获取
+    pub fn probe_length(&self) -> Result<(), AppError> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let shared = self.shared.clone();
+
+        get_rt().spawn(async move {
+            let result = shared.client.head(&shared.url).send().await;
+            let _ = tx.send(result.map(|response| response.content_length()));
+        });
+
+        match rx.recv()?? {
+            Some(length) if length > 0 => {
+                self.shared.length.store(length, Ordering::Relaxed);
+                debug!("Content length from HEAD: {}", length);
+            }
+            _ => debug!("Content length of {} is unknown", self.shared.url),
+        }
+        Ok(())
+    }
+}
+
+struct Fetch {
+    urgent: bool,
+    handle: AbortHandle,
+}
+
+/// 调度任务：按优先级排队，限制并发，合并同一块的重复请求
+async fn schedule(shared: Arc<Shared>, mut requests: mpsc::UnboundedReceiver<Request>) {
+    let mut tasks = JoinSet::new();
+    let mut in_flight: HashMap<u64, Fetch> = HashMap::new();
+    let mut ids: HashMap<Id, u64> = HashMap::new();
+    // 等待开始的块，紧急请求排在最前
+    let mut queue: VecDeque<(u64, bool)> = VecDeque::new();
+
+    loop {
+        while in_flight.len() < MAX_CONCURRENT {
+            let Some((index, urgent)) = queue.pop_front() else {
+                break;
+            };
+            if in_flight.contains_key(&index) || shared.is_cached(index) {
+                continue;
+            }
+            if shared.is_past_end(index) {
+                // 等待方读到空块即视为到达末尾
+                if urgent {
+                    shared.complete(index, Ok(Vec::new()));
+                }
+                continue;
+            }
+            let handle = tasks.spawn(fetch(shared.clone(), index));
+            ids.insert(handle.id(), index);
+            in_flight.insert(index, Fetch { urgent, handle });
+        }
+
+        tokio::select! {
+            request = requests.recv() => match request {
+                Some(Request::Fetch { index, urgent }) => {
+                    if let Some(fetch) = in_flight.get_mut(&index) {
+                        fetch.urgent |= urgent;
+                    } else if urgent {
+                        queue.retain(|(queued, _)| *queued != index);
+                        queue.push_front((index, true));
+                        // 并发已满时让出一个预取的位置
+                        if in_flight.len() >= MAX_CONCURRENT {
+                            let farthest = in_flight
+                                .iter()
+                                .filter(|(_, fetch)| !fetch.urgent)
+                                .max_by_key(|(queued, _)| queued.abs_diff(index))
+                                .map(|(queued, _)| *queued);
+                            if let Some(queued) = farthest {
+                                if let Some(fetch) = in_flight.remove(&queued) {
+                                    fetch.handle.abort();
+                                }
+                                queue.push_back((queued, false));
+                            }
+                        }
+                    } else if !queue.iter().any(|(queued, _)| *queued == index) {
+                        queue.push_back((index, false));
+                    }
+                }
+                Some(Request::Seek(index)) => {
+                    let window = index.saturating_sub(PREFETCH_BEHIND)..=index + PREFETCH_AHEAD;
+                    queue.retain(|(queued, urgent)| *urgent || window.contains(queued));
+                    in_flight.retain(|queued, fetch| {
+                        let keep = fetch.urgent || window.contains(queued);
+                        if !keep {
+                            debug!("Cancelled stale prefetch of chunk {}", queued);
+                            fetch.handle.abort();
+                        }
+                        keep
+                    });
+                    for queued in window {
+                        if !queue.iter().any(|(index, _)| *index == queued) {
+                            queue.push_back((queued, false));
+                        }
+                    }
+                }
+                None => break,
+            },
+            Some(joined) = tasks.join_next_with_id(), if !tasks.is_empty() => {
+                let (id, result) = match joined {
+                    Ok(joined) => joined,
+                    // 被取消的任务已经不在 in_flight 中
+                    Err(e) => {
+                        ids.remove(&e.id());
+                        continue;
+                    }
+                };
+                let Some(index) = ids.remove(&id) else {
+                    continue;
+                };
+                // 取消时任务可能刚好完成，这时同一块可能已经重新开始下载
+                if in_flight.get(&index).is_some_and(|fetch| fetch.handle.id() == id) {
+                    in_flight.remove(&index);
+                }
+                shared.complete(index, result);
+            }
+        }
+    }
+    debug!("Download scheduler for {} stopped", shared.url);
+}
+
+async fn fetch(shared: Arc<Shared>, index: u64) -> Result<Vec<u8>, AppError> {
+    let start = index * CHUNK_SIZE as u64;
+    let range_header = format!("bytes={}-{}", start, start + CHUNK_SIZE as u64 - 1);
+
+    let response = shared
+        .client
+        .get(&shared.url)
+        .header(RANGE, range_header)
+        .send()
+        .await?;
+    if response.status() == StatusCode::PARTIAL_CONTENT {
+        if let Some(total) = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(content_range_total)
+        {
+            shared.length.store(total, Ordering::Relaxed);
+        }
+        Ok(response.bytes().await?.to_vec())
+    } else if response.status().is_success() {
+        // 服务器忽略了 Range，返回的是完整文件，从中截取这一块
+        if let Some(total) = response.content_length() {
+            shared.length.store(total, Ordering::Relaxed);
+        }
+        let body = response.bytes().await?;
+        let rest = body.get(start as usize..).unwrap_or_default();
+        Ok(rest[..rest.len().min(CHUNK_SIZE)].to_vec())
+    } else {
+        Err(AppError::Network(format!(
+            "HTTP {} for chunk {}",
+            response.status(),
+            index
+        )))
+    }
+}
+
+/// 从 "bytes 0-262143/5242880" 中取出总长度
+fn content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
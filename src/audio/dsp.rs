@@ -0,0 +1,261 @@
+use rodio::source::SeekError;
+use rodio::{Sample, Source};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::audio::filter::Biquad;
+use crate::models::dsp::DspSettings;
+
+/// 音效处理环节，按帧处理交错排列的采样
+pub trait Effect: Send {
+    /// 设置变化时调用，应保留内部状态以免打断播放
+    fn configure(&mut self, settings: &DspSettings);
+
+    fn process(&mut self, frame: &mut [f32]);
+}
+
+struct Preamp {
+    factor: f32,
+}
+
+impl Effect for Preamp {
+    fn configure(&mut self, settings: &DspSettings) {
+        self.factor = 10f32.powf(settings.preamp_db / 20.0);
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        if self.factor != 1.0 {
+            frame.iter_mut().for_each(|sample| *sample *= self.factor);
+        }
+    }
+}
+
+struct Equalizer {
+    sample_rate: u32,
+    enabled: bool,
+    /// 每个声道一组滤波器
+    filters: Vec<Vec<Biquad>>,
+}
+
+impl Effect for Equalizer {
+    fn configure(&mut self, settings: &DspSettings) {
+        self.enabled = settings.eq_enabled;
+        let tuned: Vec<Biquad> = settings
+            .bands
+            .iter()
+            .map(|band| Biquad::equalizer(self.sample_rate, band))
+            .collect();
+
+        for filters in &mut self.filters {
+            if filters.len() == tuned.len() {
+                filters
+                    .iter_mut()
+                    .zip(&tuned)
+                    .for_each(|(filter, tuned)| filter.retune(tuned));
+            } else {
+                *filters = tuned.clone();
+            }
+        }
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        if !self.enabled {
+            return;
+        }
+        for (sample, filters) in frame.iter_mut().zip(&mut self.filters) {
+            let output = filters
+                .iter_mut()
+                .fold(f64::from(*sample), |x, filter| filter.process(x));
+            *sample = output as f32;
+        }
+    }
+}
+
+struct Mono {
+    enabled: bool,
+}
+
+impl Effect for Mono {
+    fn configure(&mut self, settings: &DspSettings) {
+        self.enabled = settings.mono;
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        if !self.enabled || frame.len() < 2 {
+            return;
+        }
+        let mixed = frame.iter().sum::<f32>() / frame.len() as f32;
+        frame.fill(mixed);
+    }
+}
+
+struct Balance {
+    left: f32,
+    right: f32,
+}
+
+impl Effect for Balance {
+    fn configure(&mut self, settings: &DspSettings) {
+        let balance = settings.balance.clamp(-1.0, 1.0);
+        self.left = (1.0 - balance).min(1.0);
+        self.right = (1.0 + balance).min(1.0);
+    }
+
+    fn process(&mut self, frame: &mut [f32]) {
+        if let [left, right, ..] = frame {
+            *left *= self.left;
+            *right *= self.right;
+        }
+    }
+}
+
+/// 按顺序排列的处理环节，新的音效加在这里
+fn chain(sample_rate: u32, channels: usize) -> Vec<Box<dyn Effect>> {
+    vec![
+        Box::new(Preamp { factor: 1.0 }),
+        Box::new(Equalizer {
+            sample_rate,
+            enabled: false,
+            filters: vec![Vec::new(); channels],
+        }),
+        Box::new(Mono { enabled: false }),
+        Box::new(Balance {
+            left: 1.0,
+            right: 1.0,
+        }),
+    ]
+}
+
+/// 所有曲目共享的音效设置
+#[derive(Default)]
+pub struct DspControl {
+    settings: Mutex<DspSettings>,
+    /// 每次修改加一，音源据此判断是否需要重新配置
+    version: AtomicU64,
+}
+
+impl DspControl {
+    pub fn new(settings: DspSettings) -> Self {
+        Self {
+            settings: Mutex::new(settings),
+            version: AtomicU64::new(0),
+        }
+    }
+
+    pub fn set(&self, settings: DspSettings) {
+        if let Ok(mut current) = self.settings.lock() {
+            *current = settings;
+            self.version.fetch_add(1, Ordering::Release);
+        }
+    }
+}
+
+/// 在解码器与 Sink 之间应用音效
+pub struct Dsp<S> {
+    inner: S,
+    control: Arc<DspControl>,
+    version: Option<u64>,
+    effects: Vec<Box<dyn Effect>>,
+    frame: Vec<f32>,
+    /// 下一个输出的采样在 `frame` 中的位置，等于长度时需要读取新的一帧
+    cursor: usize,
+}
+
+impl<S> Dsp<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    pub fn new(inner: S, control: Arc<DspControl>) -> Self {
+        let channels = usize::from(inner.channels().max(1));
+        let effects = chain(inner.sample_rate(), channels);
+        Self {
+            inner,
+            control,
+            version: None,
+            effects,
+            frame: Vec::with_capacity(channels),
+            cursor: 0,
+        }
+    }
+
+    fn sync_settings(&mut self) {
+        let version = self.control.version.load(Ordering::Acquire);
+        if self.version == Some(version) {
+            return;
+        }
+        if let Ok(settings) = self.control.settings.lock() {
+            self.effects
+                .iter_mut()
+                .for_each(|effect| effect.configure(&settings));
+            self.version = Some(version);
+        }
+    }
+
+    fn read_frame(&mut self) {
+        self.frame.clear();
+        self.cursor = 0;
+        let channels = usize::from(self.inner.channels().max(1));
+        while self.frame.len() < channels {
+            match self.inner.next() {
+                Some(sample) => self.frame.push(sample.to_f32()),
+                None => break,
+            }
+        }
+        // 流结束时最后一帧可能不完整，原样输出
+        if self.frame.len() == channels {
+            self.sync_settings();
+            for effect in &mut self.effects {
+                effect.process(&mut self.frame);
+            }
+        }
+    }
+}
+
+impl<S> Iterator for Dsp<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.cursor >= self.frame.len() {
+            self.read_frame();
+        }
+        let sample = *self.frame.get(self.cursor)?;
+        self.cursor += 1;
+        Some(sample)
+    }
+}
+
+impl<S> Source for Dsp<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        // 丢弃跳转前缓存的采样
+        self.frame.clear();
+        self.cursor = 0;
+        Ok(())
+    }
+}
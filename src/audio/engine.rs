@@ -9,7 +9,7 @@ pub fn get_backend() -> &'static AudioBackend {
         Ok(backend) => backend,
         Err(e) => {
             error!("Failed to initialize audio backend: {}", e);
-            AudioBackend::dummy() // 考虑panic
+            AudioBackend::dummy()
         }
     })
 }
@@ -0,0 +1,167 @@
+use rodio::source::SeekError;
+use rodio::{Sample, Source};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::models::audio::FadeCurve;
+
+/// 播放线程与音源之间共享的控制信号
+#[derive(Default)]
+pub struct FadeControl {
+    fade_out: Mutex<Option<(Duration, FadeCurve)>>,
+    /// 避免每个采样都去获取锁
+    fade_out_pending: AtomicBool,
+    cancelled: AtomicBool,
+    started: AtomicBool,
+}
+
+impl FadeControl {
+    /// 从当前位置开始淡出，结束后音源停止
+    pub fn fade_out(&self, duration: Duration, curve: FadeCurve) {
+        if let Ok(mut fade_out) = self.fade_out.lock() {
+            *fade_out = Some((duration, curve));
+            self.fade_out_pending.store(true, Ordering::Release);
+        }
+    }
+
+    /// 立即结束音源，用于撤回已经排入 Sink 的预加载曲目
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// 音源是否已经开始输出
+    pub fn started(&self) -> bool {
+        self.started.load(Ordering::Relaxed)
+    }
+}
+
+struct FadeOut {
+    start: u64,
+    length: u64,
+    curve: FadeCurve,
+}
+
+/// 为音源加上淡入与可随时触发的淡出
+pub struct Fade<S> {
+    inner: S,
+    control: Arc<FadeControl>,
+    fade_in: u64,
+    fade_in_curve: FadeCurve,
+    fade_out: Option<FadeOut>,
+    /// 已输出的采样数，各声道分别计数
+    played: u64,
+}
+
+impl<S> Fade<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    pub fn new(inner: S, control: Arc<FadeControl>, fade_in: Duration, curve: FadeCurve) -> Self {
+        let fade_in = samples_in(&inner, fade_in);
+        Self {
+            inner,
+            control,
+            fade_in,
+            fade_in_curve: curve,
+            fade_out: None,
+            played: 0,
+        }
+    }
+
+    fn at_frame_boundary(&self) -> bool {
+        self.played
+            .is_multiple_of(u64::from(self.inner.channels().max(1)))
+    }
+}
+
+/// 时长对应的采样数，取整到完整的帧
+fn samples_in<S>(source: &S, duration: Duration) -> u64
+where
+    S: Source,
+    S::Item: Sample,
+{
+    let frames = (duration.as_secs_f64() * f64::from(source.sample_rate())) as u64;
+    frames * u64::from(source.channels())
+}
+
+impl<S> Iterator for Fade<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        if self.control.cancelled.load(Ordering::Relaxed) && self.at_frame_boundary() {
+            return None;
+        }
+        if self.played == 0 {
+            self.control.started.store(true, Ordering::Relaxed);
+        }
+        if self.fade_out.is_none() && self.control.fade_out_pending.load(Ordering::Acquire) {
+            let order = self.control.fade_out.lock().ok().and_then(|o| *o);
+            if let Some((duration, curve)) = order {
+                self.fade_out = Some(FadeOut {
+                    start: self.played,
+                    length: samples_in(&self.inner, duration).max(1),
+                    curve,
+                });
+            }
+        }
+
+        let mut gain = 1.0;
+        if self.played < self.fade_in {
+            gain *= self
+                .fade_in_curve
+                .gain(self.played as f32 / self.fade_in as f32);
+        }
+        if let Some(fade_out) = &self.fade_out {
+            let elapsed = self.played - fade_out.start;
+            if elapsed >= fade_out.length && self.at_frame_boundary() {
+                return None;
+            }
+            gain *= fade_out
+                .curve
+                .gain(1.0 - elapsed as f32 / fade_out.length as f32);
+        }
+
+        let sample = self.inner.next()?;
+        self.played += 1;
+        Some(if gain < 1.0 {
+            sample.amplify(gain)
+        } else {
+            sample
+        })
+    }
+}
+
+impl<S> Source for Fade<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        // 跳转后不再继续淡入
+        self.played = self.played.max(self.fade_in);
+        Ok(())
+    }
+}
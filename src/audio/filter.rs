@@ -0,0 +1,127 @@
+use std::f64::consts::PI;
+
+use crate::models::dsp::{EqBand, FilterKind};
+
+/// 二阶 IIR 滤波器，转置直接 II 型
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    /// 系数已按 `a0` 归一化
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self {
+            b0: b[0],
+            b1: b[1],
+            b2: b[2],
+            a1: a[0],
+            a2: a[1],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// ITU-R BS.1770 K 计权的第一级：模拟头部声学效应的高架滤波
+    pub fn k_shelf(sample_rate: u32) -> Self {
+        let f0 = 1681.974450955533;
+        let gain = 3.999843853973347;
+        let q = 0.7071752369554196;
+
+        let k = (PI * f0 / f64::from(sample_rate)).tan();
+        let vh = 10f64.powf(gain / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        Self::new(
+            [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        )
+    }
+
+    /// ITU-R BS.1770 K 计权的第二级：RLB 高通
+    pub fn k_highpass(sample_rate: u32) -> Self {
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+
+        let k = (PI * f0 / f64::from(sample_rate)).tan();
+        let a0 = 1.0 + k / q + k * k;
+        Self::new(
+            [1.0, -2.0, 1.0],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        )
+    }
+
+    /// 按 RBJ Audio EQ Cookbook 计算均衡器频段的系数
+    pub fn equalizer(sample_rate: u32, band: &EqBand) -> Self {
+        let rate = f64::from(sample_rate);
+        // 频率不能超过奈奎斯特频率
+        let frequency = f64::from(band.frequency).clamp(10.0, rate * 0.45);
+        let q = f64::from(band.q).max(0.1);
+        let a = 10f64.powf(f64::from(band.gain_db) / 40.0);
+        let w0 = 2.0 * PI * frequency / rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        let shelf = 2.0 * a.sqrt() * alpha;
+
+        let (num, den) = match band.kind {
+            FilterKind::Peaking => (
+                [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+                [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+            ),
+            FilterKind::LowShelf => (
+                [
+                    a * ((a + 1.0) - (a - 1.0) * cos + shelf),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - shelf),
+                ],
+                [
+                    (a + 1.0) + (a - 1.0) * cos + shelf,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - shelf,
+                ],
+            ),
+            FilterKind::HighShelf => (
+                [
+                    a * ((a + 1.0) + (a - 1.0) * cos + shelf),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - shelf),
+                ],
+                [
+                    (a + 1.0) - (a - 1.0) * cos + shelf,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - shelf,
+                ],
+            ),
+        };
+        Self::new(
+            [num[0] / den[0], num[1] / den[0], num[2] / den[0]],
+            [den[1] / den[0], den[2] / den[0]],
+        )
+    }
+
+    /// 替换系数并保留滤波器状态，参数变化时不会产生爆音
+    pub fn retune(&mut self, other: &Biquad) {
+        self.b0 = other.b0;
+        self.b1 = other.b1;
+        self.b2 = other.b2;
+        self.a1 = other.a1;
+        self.a2 = other.a2;
+    }
+
+    pub fn process(&mut self, x: f64) -> f64 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
@@ -0,0 +1,270 @@
+use log::debug;
+use rodio::source::SeekError;
+use rodio::{Sample, Source};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::audio::filter::Biquad;
+use crate::models::audio::Loudness;
+use crate::storage::loudness::get_loudness_store;
+
+/// 绝对门限，单位 LUFS
+const ABSOLUTE_GATE: f64 = -70.0;
+/// 相对门限，低于未加相对门限时的响度
+const RELATIVE_GATE: f64 = 10.0;
+/// 每个测量块由 4 个 100ms 的子块组成，相邻块重叠 75%
+const SUB_BLOCKS: usize = 4;
+/// 实际测量到的时长不足曲目时长的这个比例时不保存结果
+const MIN_COVERAGE: f64 = 0.8;
+
+fn energy_to_lufs(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+/// 按 ITU-R BS.1770 测量积分响度
+pub struct LoudnessMeter {
+    sample_rate: u32,
+    channels: usize,
+    filters: Vec<(Biquad, Biquad)>,
+    channel: usize,
+    /// 当前子块内的平方和与帧数
+    sum: f64,
+    frames_in_sub_block: usize,
+    sub_block_frames: usize,
+    recent: VecDeque<f64>,
+    /// 每个 400ms 测量块的均方值
+    blocks: Vec<f64>,
+    frames: u64,
+    peak: f32,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        let channels = usize::from(channels.max(1));
+        Self {
+            sample_rate,
+            channels,
+            filters: (0..channels)
+                .map(|_| {
+                    (
+                        Biquad::k_shelf(sample_rate),
+                        Biquad::k_highpass(sample_rate),
+                    )
+                })
+                .collect(),
+            channel: 0,
+            sum: 0.0,
+            frames_in_sub_block: 0,
+            sub_block_frames: (sample_rate as usize / 10).max(1),
+            recent: VecDeque::with_capacity(SUB_BLOCKS),
+            blocks: Vec::new(),
+            frames: 0,
+            peak: 0.0,
+        }
+    }
+
+    /// 送入一个交错排列的采样
+    pub fn push(&mut self, sample: f32) {
+        self.peak = self.peak.max(sample.abs());
+
+        let (shelf, highpass) = &mut self.filters[self.channel];
+        let filtered = highpass.process(shelf.process(f64::from(sample)));
+        self.sum += filtered * filtered;
+
+        self.channel += 1;
+        if self.channel < self.channels {
+            return;
+        }
+        self.channel = 0;
+        self.frames += 1;
+        self.frames_in_sub_block += 1;
+        if self.frames_in_sub_block < self.sub_block_frames {
+            return;
+        }
+
+        if self.recent.len() == SUB_BLOCKS {
+            self.recent.pop_front();
+        }
+        self.recent
+            .push_back(self.sum / self.sub_block_frames as f64);
+        if self.recent.len() == SUB_BLOCKS {
+            self.blocks
+                .push(self.recent.iter().sum::<f64>() / SUB_BLOCKS as f64);
+        }
+        self.sum = 0.0;
+        self.frames_in_sub_block = 0;
+    }
+
+    /// 已测量的音频时长
+    pub fn measured(&self) -> Duration {
+        Duration::from_secs_f64(self.frames as f64 / f64::from(self.sample_rate.max(1)))
+    }
+
+    /// 积分响度，有效的测量块不足时为 `None`
+    pub fn result(&self) -> Option<Loudness> {
+        let gated: Vec<f64> = self
+            .blocks
+            .iter()
+            .copied()
+            .filter(|energy| energy_to_lufs(*energy) > ABSOLUTE_GATE)
+            .collect();
+        if gated.is_empty() {
+            return None;
+        }
+
+        let threshold =
+            energy_to_lufs(gated.iter().sum::<f64>() / gated.len() as f64) - RELATIVE_GATE;
+        let gated: Vec<f64> = gated
+            .into_iter()
+            .filter(|energy| energy_to_lufs(*energy) > threshold)
+            .collect();
+        if gated.is_empty() {
+            return None;
+        }
+
+        Some(Loudness {
+            integrated: energy_to_lufs(gated.iter().sum::<f64>() / gated.len() as f64),
+            peak: self.peak,
+        })
+    }
+}
+
+/// 可以在播放中修改的线性增益
+#[derive(Debug)]
+pub struct GainControl(AtomicU32);
+
+impl GainControl {
+    pub fn new(factor: f32) -> Self {
+        Self(AtomicU32::new(factor.to_bits()))
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, factor: f32) {
+        self.0.store(factor.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// 正在测量的曲目，音源释放时保存结果
+struct Measurement {
+    meter: LoudnessMeter,
+    song_id: u64,
+    album_id: u64,
+    duration: Option<Duration>,
+    finished: bool,
+}
+
+impl Drop for Measurement {
+    fn drop(&mut self) {
+        // 淡出或无缝切换时音源会在末尾之前结束，只要覆盖了大部分就认为有效
+        let covered = self.finished
+            || self.duration.is_some_and(|duration| {
+                self.meter.measured().as_secs_f64() >= duration.as_secs_f64() * MIN_COVERAGE
+            });
+        if !covered {
+            return;
+        }
+        let Some(loudness) = self.meter.result() else {
+            return;
+        };
+
+        debug!(
+            "Measured {}: {:.1} LUFS, peak {:.3}",
+            self.song_id, loudness.integrated, loudness.peak
+        );
+        let (song_id, album_id) = (self.song_id, self.album_id);
+        // 音源在输出线程中释放，另开线程写入，避免阻塞音频回调
+        thread::spawn(move || get_loudness_store().save(song_id, album_id, loudness));
+    }
+}
+
+/// 对音源应用归一化增益，没有增益数据时顺便测量响度
+pub struct Normalize<S> {
+    inner: S,
+    gain: Arc<GainControl>,
+    measurement: Option<Measurement>,
+}
+
+impl<S> Normalize<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    pub fn new(inner: S, gain: Arc<GainControl>) -> Self {
+        Self {
+            inner,
+            gain,
+            measurement: None,
+        }
+    }
+
+    /// 播放的同时测量响度，结果按歌曲缓存
+    pub fn measure(mut self, song_id: u64, album_id: u64, duration: Option<Duration>) -> Self {
+        self.measurement = Some(Measurement {
+            meter: LoudnessMeter::new(self.inner.sample_rate(), self.inner.channels()),
+            song_id,
+            album_id,
+            duration,
+            finished: false,
+        });
+        self
+    }
+}
+
+impl<S> Iterator for Normalize<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        let Some(sample) = self.inner.next() else {
+            if let Some(measurement) = &mut self.measurement {
+                measurement.finished = true;
+            }
+            return None;
+        };
+        if let Some(measurement) = &mut self.measurement {
+            measurement.meter.push(sample.to_f32());
+        }
+
+        let factor = self.gain.get();
+        Some(if factor == 1.0 {
+            sample
+        } else {
+            sample.amplify(factor)
+        })
+    }
+}
+
+impl<S> Source for Normalize<S>
+where
+    S: Source,
+    S::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)
+    }
+}
@@ -1,5 +1,16 @@
 pub mod backend;
 // mod buffer;
+pub mod deck;
+pub mod decoder;
+pub mod download;
+pub mod dsp;
 pub mod engine;
+pub mod fade;
+pub mod filter;
+pub mod loudness;
+pub mod output;
 // pub mod integration;
+pub mod queue;
 pub mod reader;
+pub mod source;
+pub mod tags;
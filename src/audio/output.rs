@@ -0,0 +1,306 @@
+use log::{debug, warn};
+use rodio::cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rodio::cpal::{self, FromSample, SampleFormat, SizedSample, StreamConfig};
+use rodio::dynamic_mixer::{self, DynamicMixer, DynamicMixerController};
+use rodio::{Device, Sink};
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+use crate::models::audio::{DeviceConfig, OutputDevice, OutputKind};
+
+/// 无声输出与 WAV 文件使用的格式
+const CHANNELS: u16 = 2;
+const SAMPLE_RATE: u32 = 44100;
+/// 无声输出每次取出 10ms 的采样
+const BLOCK_FRAMES: u32 = SAMPLE_RATE / 100;
+/// WAV 文件头的更新间隔，进程意外退出时文件仍然可以打开
+const HEADER_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 以 32 位浮点格式写入的 WAV 文件
+struct WavWriter {
+    file: BufWriter<File>,
+    /// 已写入的数据字节数
+    length: u32,
+}
+
+impl WavWriter {
+    fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = Self {
+            file: BufWriter::new(File::create(path)?),
+            length: 0,
+        };
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let block_align = CHANNELS * 4;
+        let file = &mut self.file;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(b"RIFF")?;
+        file.write_all(&(36 + self.length).to_le_bytes())?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        // 3 为 IEEE 浮点
+        file.write_all(&3u16.to_le_bytes())?;
+        file.write_all(&CHANNELS.to_le_bytes())?;
+        file.write_all(&SAMPLE_RATE.to_le_bytes())?;
+        file.write_all(&(SAMPLE_RATE * u32::from(block_align)).to_le_bytes())?;
+        file.write_all(&block_align.to_le_bytes())?;
+        file.write_all(&32u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&self.length.to_le_bytes())?;
+        file.seek(SeekFrom::End(0))?;
+        file.flush()
+    }
+
+    fn write(&mut self, sample: f32) -> io::Result<()> {
+        self.file.write_all(&sample.to_le_bytes())?;
+        self.length = self.length.saturating_add(4);
+        Ok(())
+    }
+}
+
+/// 代替声卡消耗采样，按设定的倍速推进播放进度
+fn drain(
+    mut mixer: DynamicMixer<f32>,
+    mut writer: Option<WavWriter>,
+    speed: u32,
+    stop: Arc<AtomicBool>,
+) {
+    let start = Instant::now();
+    let mut last_header = start;
+    let mut frames = 0u64;
+    let rate = f64::from(SAMPLE_RATE) * f64::from(speed.max(1));
+
+    while !stop.load(Ordering::Relaxed) {
+        for _ in 0..BLOCK_FRAMES * u32::from(CHANNELS) {
+            let sample = mixer.next().unwrap_or(0.0);
+            if let Some(file) = &mut writer {
+                if let Err(e) = file.write(sample) {
+                    warn!("Failed to write audio output: {}", e);
+                    writer = None;
+                }
+            }
+        }
+        frames += u64::from(BLOCK_FRAMES);
+
+        if last_header.elapsed() >= HEADER_UPDATE_INTERVAL {
+            last_header = Instant::now();
+            if let Some(Err(e)) = writer.as_mut().map(WavWriter::write_header) {
+                warn!("Failed to update wav header: {}", e);
+            }
+        }
+        let due = Duration::from_secs_f64(frames as f64 / rate);
+        if let Some(wait) = due.checked_sub(start.elapsed()) {
+            thread::sleep(wait);
+        }
+    }
+
+    if let Some(Err(e)) = writer.as_mut().map(WavWriter::write_header) {
+        warn!("Failed to finish wav file: {}", e);
+    }
+}
+
+fn device_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Audio(e.to_string())
+}
+
+/// 列出可用的输出设备及其支持的格式
+///
+/// 部分系统上被其他程序独占的设备不会出现在列表中
+pub fn devices() -> Vec<OutputDevice> {
+    let host = cpal::default_host();
+    let default = host
+        .default_output_device()
+        .and_then(|device| device.name().ok());
+    let devices = match host.output_devices() {
+        Ok(devices) => devices,
+        Err(e) => {
+            warn!("Failed to list output devices: {}", e);
+            return Vec::new();
+        }
+    };
+
+    devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let configs = device
+                .supported_output_configs()
+                .map(|configs| {
+                    configs
+                        .map(|config| DeviceConfig {
+                            channels: config.channels(),
+                            min_sample_rate: config.min_sample_rate().0,
+                            max_sample_rate: config.max_sample_rate().0,
+                            sample_format: config.sample_format().to_string(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(OutputDevice {
+                is_default: default.as_deref() == Some(name.as_str()),
+                name,
+                configs,
+            })
+        })
+        .collect()
+}
+
+fn find_device(name: Option<&str>) -> Result<Device, AppError> {
+    let host = cpal::default_host();
+    let Some(name) = name else {
+        return host
+            .default_output_device()
+            .ok_or_else(|| AppError::Audio("No default output device".to_string()));
+    };
+    host.output_devices()
+        .map_err(device_error)?
+        .find(|device| device.name().is_ok_and(|n| n == name))
+        .ok_or_else(|| AppError::Audio(format!("Output device not found: {}", name)))
+}
+
+fn build_stream<T>(
+    device: &Device,
+    config: &StreamConfig,
+    mut mixer: DynamicMixer<f32>,
+    lost: Arc<AtomicBool>,
+) -> Result<cpal::Stream, AppError>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    device
+        .build_output_stream::<T, _, _>(
+            config,
+            move |data: &mut [T], _| {
+                for sample in data.iter_mut() {
+                    *sample = T::from_sample(mixer.next().unwrap_or(0.0));
+                }
+            },
+            move |e| {
+                // 设备拔出或被系统移除后流不再可用，由播放线程切换到默认设备
+                warn!("Audio output stream error: {}", e);
+                lost.store(true, Ordering::Relaxed);
+            },
+            None,
+        )
+        .map_err(device_error)
+}
+
+enum Target {
+    Device {
+        _stream: cpal::Stream,
+        lost: Arc<AtomicBool>,
+    },
+    /// 由后台线程消耗混音结果
+    Headless {
+        stop: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    },
+}
+
+/// 播放用的 Sink 都从这里创建
+pub struct Output {
+    mixer: Arc<DynamicMixerController<f32>>,
+    target: Target,
+    name: String,
+}
+
+impl Output {
+    pub fn open(kind: &OutputKind) -> Result<Self, AppError> {
+        let output = match kind {
+            OutputKind::Device(name) => Self::device(name.as_deref())?,
+            OutputKind::Null { speed } => Self::headless(None, *speed, "null".to_string()),
+            OutputKind::Wav { path, speed } => {
+                let writer = WavWriter::create(path).map_err(|e| {
+                    AppError::Audio(format!("Failed to create {}: {}", path.display(), e))
+                })?;
+                Self::headless(Some(writer), *speed, path.display().to_string())
+            }
+        };
+        debug!("Opened audio output {}", output.name);
+        Ok(output)
+    }
+
+    fn device(name: Option<&str>) -> Result<Self, AppError> {
+        let device = find_device(name)?;
+        let supported = device.default_output_config().map_err(device_error)?;
+        let config = supported.config();
+        let (mixer, output) = dynamic_mixer::mixer(config.channels, config.sample_rate.0);
+        let lost = Arc::new(AtomicBool::new(false));
+
+        let stream = match supported.sample_format() {
+            SampleFormat::F32 => build_stream::<f32>(&device, &config, output, lost.clone()),
+            SampleFormat::I16 => build_stream::<i16>(&device, &config, output, lost.clone()),
+            SampleFormat::U16 => build_stream::<u16>(&device, &config, output, lost.clone()),
+            SampleFormat::I32 => build_stream::<i32>(&device, &config, output, lost.clone()),
+            format => Err(AppError::Audio(format!(
+                "Unsupported sample format: {}",
+                format
+            ))),
+        }?;
+        stream.play().map_err(device_error)?;
+
+        Ok(Self {
+            mixer,
+            target: Target::Device {
+                _stream: stream,
+                lost,
+            },
+            name: device.name().map_err(device_error)?,
+        })
+    }
+
+    fn headless(writer: Option<WavWriter>, speed: u32, name: String) -> Self {
+        let (mixer, output) = dynamic_mixer::mixer(CHANNELS, SAMPLE_RATE);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = thread::spawn({
+            let stop = stop.clone();
+            move || drain(output, writer, speed, stop)
+        });
+        Self {
+            mixer,
+            target: Target::Headless {
+                stop,
+                thread: Some(thread),
+            },
+            name,
+        }
+    }
+
+    pub fn sink(&self) -> Result<Sink, AppError> {
+        let (sink, queue) = Sink::new_idle();
+        self.mixer.add(queue);
+        Ok(sink)
+    }
+
+    /// 设备名称，无声输出为 "null"，WAV 输出为文件路径
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 输出设备是否已经失效
+    pub fn lost(&self) -> bool {
+        match &self.target {
+            Target::Device { lost, .. } => lost.load(Ordering::Relaxed),
+            Target::Headless { .. } => false,
+        }
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        if let Target::Headless { stop, thread, .. } = &mut self.target {
+            stop.store(true, Ordering::Relaxed);
+            if let Some(thread) = thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
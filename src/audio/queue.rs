@@ -0,0 +1,493 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::audio::PlayMode;
+use crate::models::song::Song;
+
+/// splitmix64，保证同一种子在不同版本中得到相同的随机顺序
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+fn random_below(state: &mut u64, bound: usize) -> usize {
+    (next_random(state) % bound as u64) as usize
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueueEntry {
+    /// 队列内唯一的编号，随机顺序通过它引用歌曲，不受增删与移动影响
+    key: u64,
+    song: Song,
+}
+
+/// 播放队列
+///
+/// `entries` 为展示顺序；随机模式下另有 `order` 记录播放顺序，
+/// 由 `rng` 状态决定，相同的种子与操作序列得到相同的顺序
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Queue {
+    entries: Vec<QueueEntry>,
+    order: Vec<u64>,
+    current: Option<u64>,
+    mode: PlayMode,
+    rng: u64,
+    next_key: u64,
+    /// 每次修改后递增，用于判断是否需要保存和通知界面
+    #[serde(skip)]
+    revision: u64,
+}
+
+impl Queue {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: seed,
+            ..Default::default()
+        }
+    }
+
+    pub fn songs(&self) -> impl Iterator<Item = &Song> {
+        self.entries.iter().map(|e| &e.song)
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn mode(&self) -> PlayMode {
+        self.mode
+    }
+
+    /// 当前歌曲在展示顺序中的位置
+    pub fn current_index(&self) -> Option<usize> {
+        let key = self.current?;
+        self.entries.iter().position(|e| e.key == key)
+    }
+
+    pub fn current(&self) -> Option<&Song> {
+        self.current_index().map(|i| &self.entries[i].song)
+    }
+
+    /// 当前歌曲之后还有多少首待播，循环模式下不计回绕
+    pub fn remaining(&self) -> usize {
+        let sequence = self.sequence();
+        match self
+            .current
+            .and_then(|key| sequence.iter().position(|k| *k == key))
+        {
+            Some(pos) => sequence.len() - pos - 1,
+            None => sequence.len(),
+        }
+    }
+
+    /// 播放顺序
+    fn sequence(&self) -> Vec<u64> {
+        match self.mode {
+            PlayMode::Shuffle => self.order.clone(),
+            _ => self.entries.iter().map(|e| e.key).collect(),
+        }
+    }
+
+    fn make_entries(&mut self, songs: Vec<Song>) -> Vec<QueueEntry> {
+        songs
+            .into_iter()
+            .map(|song| {
+                self.next_key += 1;
+                QueueEntry {
+                    key: self.next_key,
+                    song,
+                }
+            })
+            .collect()
+    }
+
+    /// 清空队列并放入新的歌曲，当前歌曲指向 `start`，越界时指向第一首
+    pub fn replace(&mut self, songs: Vec<Song>, start: usize) {
+        self.revision += 1;
+        self.entries.clear();
+        self.order.clear();
+        self.current = None;
+        self.append(songs);
+        self.current = self
+            .entries
+            .get(start)
+            .or(self.entries.first())
+            .map(|e| e.key);
+        if self.mode == PlayMode::Shuffle {
+            self.reshuffle();
+        }
+    }
+
+    pub fn append(&mut self, songs: Vec<Song>) {
+        self.revision += 1;
+        let entries = self.make_entries(songs);
+        if self.mode == PlayMode::Shuffle {
+            // 随机插入到当前歌曲之后的位置
+            let start = self
+                .current
+                .and_then(|key| self.order.iter().position(|k| *k == key))
+                .map_or(0, |pos| pos + 1);
+            for entry in &entries {
+                let pos = start + random_below(&mut self.rng, self.order.len() - start + 1);
+                self.order.insert(pos, entry.key);
+            }
+        }
+        self.entries.extend(entries);
+    }
+
+    /// 插入到当前歌曲之后，随机模式下同样紧接着播放
+    pub fn insert_next(&mut self, songs: Vec<Song>) {
+        self.revision += 1;
+        let entries = self.make_entries(songs);
+        let index = self.current_index().map_or(0, |i| i + 1);
+        if self.mode == PlayMode::Shuffle {
+            let pos = self
+                .current
+                .and_then(|key| self.order.iter().position(|k| *k == key))
+                .map_or(0, |pos| pos + 1);
+            for (offset, entry) in entries.iter().enumerate() {
+                self.order.insert(pos + offset, entry.key);
+            }
+        }
+        self.entries.splice(index..index, entries);
+    }
+
+    /// 移除歌曲，返回被移除的是否为当前歌曲
+    ///
+    /// 移除当前歌曲时当前位置顺延到下一首
+    pub fn remove(&mut self, index: usize) -> bool {
+        if index >= self.entries.len() {
+            return false;
+        }
+        self.revision += 1;
+        let key = self.entries[index].key;
+        let is_current = self.current == Some(key);
+        if is_current {
+            self.current = self.step(true, false);
+            if self.current == Some(key) {
+                self.current = None;
+            }
+        }
+
+        self.entries.remove(index);
+        self.order.retain(|k| *k != key);
+        is_current
+    }
+
+    /// 调整展示顺序，不影响随机顺序
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from >= self.entries.len() || to >= self.entries.len() {
+            return;
+        }
+        self.revision += 1;
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.revision += 1;
+        self.entries.clear();
+        self.order.clear();
+        self.current = None;
+    }
+
+    pub fn jump(&mut self, index: usize) -> Option<&Song> {
+        let key = self.entries.get(index)?.key;
+        self.revision += 1;
+        self.current = Some(key);
+        self.current()
+    }
+
+    pub fn set_mode(&mut self, mode: PlayMode) {
+        if self.mode == mode {
+            return;
+        }
+        self.revision += 1;
+        self.mode = mode;
+        if mode == PlayMode::Shuffle {
+            self.reshuffle();
+        } else {
+            self.order.clear();
+        }
+    }
+
+    /// 重新生成随机顺序，当前歌曲排在最前
+    fn reshuffle(&mut self) {
+        let mut keys: Vec<u64> = self
+            .entries
+            .iter()
+            .map(|e| e.key)
+            .filter(|k| Some(*k) != self.current)
+            .collect();
+        for i in (1..keys.len()).rev() {
+            let j = random_below(&mut self.rng, i + 1);
+            keys.swap(i, j);
+        }
+        self.order = self.current.into_iter().chain(keys).collect();
+    }
+
+    /// 当前歌曲自然播放结束后将要播放的歌曲，不改变队列
+    ///
+    /// 随机模式一轮结束时需要重新洗牌，此时无法预知，返回 `None`
+    pub fn upcoming(&self) -> Option<&Song> {
+        if self.mode == PlayMode::RepeatOne {
+            return self.current();
+        }
+
+        let sequence = self.sequence();
+        let key = match self
+            .current
+            .and_then(|key| sequence.iter().position(|k| *k == key))
+        {
+            Some(pos) => match sequence.get(pos + 1) {
+                Some(key) => *key,
+                None if self.mode == PlayMode::RepeatAll => *sequence.first()?,
+                None => return None,
+            },
+            None => *sequence.first()?,
+        };
+        self.entries.iter().find(|e| e.key == key).map(|e| &e.song)
+    }
+
+    /// 计算下一首，`auto` 表示当前歌曲自然播放结束
+    fn step(&mut self, forward: bool, auto: bool) -> Option<u64> {
+        if auto && self.mode == PlayMode::RepeatOne {
+            return self.current;
+        }
+
+        let sequence = self.sequence();
+        if sequence.is_empty() {
+            return None;
+        }
+        let Some(pos) = self
+            .current
+            .and_then(|key| sequence.iter().position(|k| *k == key))
+        else {
+            return sequence.first().copied();
+        };
+
+        let wraps = matches!(self.mode, PlayMode::RepeatAll | PlayMode::RepeatOne);
+        if forward {
+            match sequence.get(pos + 1) {
+                Some(key) => Some(*key),
+                None if wraps => sequence.first().copied(),
+                None if self.mode == PlayMode::Shuffle => {
+                    // 随机模式播完一轮后换一个顺序继续
+                    self.current = None;
+                    self.reshuffle();
+                    self.order.first().copied()
+                }
+                None => None,
+            }
+        } else {
+            match pos.checked_sub(1) {
+                Some(prev) => Some(sequence[prev]),
+                None if wraps => sequence.last().copied(),
+                None => Some(sequence[pos]),
+            }
+        }
+    }
+
+    /// 切到下一首并返回它，队列已播完时返回 `None` 且当前位置不变
+    pub fn next(&mut self, auto: bool) -> Option<&Song> {
+        let key = self.step(true, auto)?;
+        self.revision += 1;
+        self.current = Some(key);
+        self.current()
+    }
+
+    pub fn previous(&mut self) -> Option<&Song> {
+        let key = self.step(false, false)?;
+        self.revision += 1;
+        self.current = Some(key);
+        self.current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn songs(ids: impl IntoIterator<Item = u64>) -> Vec<Song> {
+        ids.into_iter()
+            .map(|id| serde_json::from_value(serde_json::json!({ "id": id })).unwrap())
+            .collect()
+    }
+
+    fn ids(queue: &Queue) -> Vec<u64> {
+        queue.songs().map(|s| s.id).collect()
+    }
+
+    fn current_id(queue: &Queue) -> Option<u64> {
+        queue.current().map(|s| s.id)
+    }
+
+    /// 手动切歌直到本轮结束，返回包括当前歌曲在内的播放顺序
+    fn play_round(queue: &mut Queue) -> Vec<u64> {
+        let mut played: Vec<u64> = current_id(queue).into_iter().collect();
+        while queue.remaining() > 0 {
+            played.extend(queue.next(false).map(|s| s.id));
+        }
+        played
+    }
+
+    fn sorted(mut ids: Vec<u64>) -> Vec<u64> {
+        ids.sort_unstable();
+        ids
+    }
+
+    #[test]
+    fn replace_starts_at_index() {
+        let mut queue = Queue::with_seed(1);
+        queue.replace(songs(1..=5), 2);
+        assert_eq!(queue.current().map(|s| s.id), Some(3));
+        assert_eq!(queue.next(false).map(|s| s.id), Some(4));
+
+        queue.replace(songs(1..=3), 10);
+        assert_eq!(queue.current().map(|s| s.id), Some(1));
+
+        queue.set_mode(PlayMode::Shuffle);
+        queue.replace(songs(1..=5), 3);
+        assert_eq!(queue.current().map(|s| s.id), Some(4));
+        assert_eq!(queue.remaining(), 4);
+    }
+
+    #[test]
+    fn revision_tracks_changes() {
+        let mut queue = Queue::with_seed(1);
+        queue.replace(songs(1..=3), 0);
+        let revision = queue.revision();
+
+        // 无效操作不改变队列
+        queue.move_item(0, 5);
+        queue.remove(5);
+        assert!(queue.jump(5).is_none());
+        queue.set_mode(PlayMode::Sequential);
+        assert_eq!(queue.upcoming().map(|s| s.id), Some(2));
+        assert_eq!(queue.revision(), revision);
+
+        queue.move_item(0, 2);
+        assert_eq!(ids(&queue), [2, 3, 1]);
+        assert!(queue.revision() > revision);
+
+        // 当前歌曲已在末尾，顺序播放时没有下一首
+        let revision = queue.revision();
+        assert!(queue.next(false).is_none());
+        assert_eq!(queue.revision(), revision);
+        assert!(queue.previous().is_some());
+        assert!(queue.revision() > revision);
+    }
+
+    #[test]
+    fn shuffle_is_reproducible_with_seed() {
+        let shuffled = |seed| {
+            let mut queue = Queue::with_seed(seed);
+            queue.set_mode(PlayMode::Shuffle);
+            queue.replace(songs(1..=10), 0);
+            queue.append(songs(11..=13));
+            play_round(&mut queue)
+        };
+
+        let order = shuffled(42);
+        assert_eq!(order, shuffled(42));
+        assert_eq!(order[0], 1);
+        assert_eq!(sorted(order.clone()), (1..=13).collect::<Vec<_>>());
+        assert_ne!(order, shuffled(7));
+    }
+
+    #[test]
+    fn shuffle_reshuffles_after_round() {
+        let mut queue = Queue::with_seed(7);
+        queue.set_mode(PlayMode::Shuffle);
+        queue.replace(songs(1..=5), 0);
+
+        let first = play_round(&mut queue);
+        assert_eq!(sorted(first), [1, 2, 3, 4, 5]);
+        assert!(queue.upcoming().is_none());
+
+        // 本轮播完后重新洗牌继续，新的一轮同样覆盖全部歌曲
+        assert!(queue.next(true).is_some());
+        assert_eq!(queue.remaining(), 4);
+        assert_eq!(sorted(play_round(&mut queue)), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn repeat_one_stays_on_track() {
+        let mut queue = Queue::with_seed(1);
+        queue.replace(songs(1..=3), 0);
+        queue.set_mode(PlayMode::RepeatOne);
+
+        assert_eq!(queue.upcoming().map(|s| s.id), Some(1));
+        assert_eq!(queue.next(true).map(|s| s.id), Some(1));
+        assert_eq!(queue.next(true).map(|s| s.id), Some(1));
+        // 手动切歌仍然前进
+        assert_eq!(queue.next(false).map(|s| s.id), Some(2));
+        assert_eq!(queue.next(true).map(|s| s.id), Some(2));
+    }
+
+    #[test]
+    fn repeat_all_wraps_both_ways() {
+        let mut queue = Queue::with_seed(1);
+        queue.replace(songs(1..=3), 2);
+        queue.set_mode(PlayMode::RepeatAll);
+
+        assert_eq!(queue.upcoming().map(|s| s.id), Some(1));
+        assert_eq!(queue.next(true).map(|s| s.id), Some(1));
+        assert_eq!(queue.previous().map(|s| s.id), Some(3));
+        assert_eq!(queue.previous().map(|s| s.id), Some(2));
+        assert_eq!(queue.next(false).map(|s| s.id), Some(3));
+        assert_eq!(queue.next(false).map(|s| s.id), Some(1));
+    }
+
+    #[test]
+    fn insert_next_and_append_in_shuffle() {
+        let mut queue = Queue::with_seed(3);
+        queue.set_mode(PlayMode::Shuffle);
+        queue.replace(songs(1..=6), 0);
+
+        // 插播的歌曲紧跟当前歌曲，展示顺序中也排在它后面
+        queue.insert_next(songs([100, 101]));
+        assert_eq!(ids(&queue), [1, 100, 101, 2, 3, 4, 5, 6]);
+        assert_eq!(queue.upcoming().map(|s| s.id), Some(100));
+        assert_eq!(queue.next(false).map(|s| s.id), Some(100));
+        assert_eq!(queue.next(false).map(|s| s.id), Some(101));
+
+        // 追加的歌曲排在展示顺序末尾，在本轮剩余的部分中播放
+        let remaining = queue.remaining();
+        queue.append(songs([200]));
+        assert_eq!(ids(&queue).last(), Some(&200));
+        assert_eq!(queue.remaining(), remaining + 1);
+        let rest = play_round(&mut queue);
+        assert_eq!(sorted(rest), [2, 3, 4, 5, 6, 101, 200]);
+    }
+
+    #[test]
+    fn remove_current_moves_to_next() {
+        let mut queue = Queue::with_seed(1);
+        queue.replace(songs(1..=4), 1);
+
+        assert!(!queue.remove(0));
+        assert_eq!(current_id(&queue), Some(2));
+
+        assert!(queue.remove(0));
+        assert_eq!(ids(&queue), [3, 4]);
+        assert_eq!(current_id(&queue), Some(3));
+
+        // 移除末尾的当前歌曲后没有下一首，当前位置清空
+        queue.jump(1);
+        assert!(queue.remove(1));
+        assert_eq!(ids(&queue), [3]);
+        assert_eq!(current_id(&queue), None);
+    }
+}
@@ -1,141 +1,68 @@
-use log::{debug, error};
-use reqwest::header::{HeaderMap, HeaderValue, RANGE};
-use std::collections::HashMap;
+use log::debug;
 use std::io::{Read, Seek, SeekFrom};
-use std::sync::{Arc, Mutex, OnceLock};
-use std::time::Duration;
-use tokio::runtime::Runtime;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
 
+use crate::audio::download::{CHUNK_SIZE, Downloads};
 use crate::error::AppError;
 
-const CHUNK_SIZE: usize = 256 * 1024;
-const MAX_CACHED_CHUNKS: usize = 8;
-
-static RT: OnceLock<Runtime> = OnceLock::new();
-
-fn get_rt() -> &'static Runtime {
-    RT.get_or_init(|| Runtime::new().expect("Failed to create runtime"))
-}
-
 #[derive(Clone)]
 pub struct Reader {
-    url: String,
-    client: reqwest::Client,
     position: Arc<Mutex<u64>>,
-    chunks: Arc<Mutex<HashMap<u64, Arc<Vec<u8>>>>>,
+    downloads: Downloads,
 }
 
 impl Reader {
     pub fn new(url: String) -> Self {
-        let client = reqwest::Client::builder()
-            .default_headers({
-                let mut headers = HeaderMap::new();
-                headers.insert("User-Agent", HeaderValue::from_static("AudioReader/1.0"));
-                headers.insert("Accept-Ranges", HeaderValue::from_static("bytes"));
-                headers
-            })
-            .timeout(Duration::from_secs(30))
-            .connect_timeout(Duration::from_secs(10))
-            .pool_max_idle_per_host(6)
-            .pool_idle_timeout(Duration::from_secs(90))
-            .build()
-            .expect("Failed to create HTTP client");
-
+        let position = Arc::new(Mutex::new(0));
         Self {
-            url,
-            client,
-            position: Arc::new(Mutex::new(0)),
-            chunks: Arc::new(Mutex::new(HashMap::new())),
+            downloads: Downloads::new(url, position.clone()),
+            position,
         }
     }
 
-    pub fn wait_preload(&self, chunk_count: u64) -> Result<(), AppError> {
-        let start_chunk = *self.position.lock()? / CHUNK_SIZE as u64;
-        debug!("Preloading {} data chunks...", chunk_count);
-
-        let handles: Vec<_> = (0..chunk_count)
-            .map(|i| {
-                let self_clone = self.clone();
-                let chunk_index = start_chunk + i;
-                std::thread::spawn(move || self_clone.get_chunk(chunk_index))
-            })
-            .collect();
-
-        for handle in handles {
-            if let Err(e) = handle.join() {
-                error!("Preload task failed: {:?}", e);
-            }
-        }
-        Ok(())
+    /// 数据总长度，还没有收到过响应时为 `None`
+    pub fn len(&self) -> Option<u64> {
+        self.downloads.len()
     }
 
-    fn fetch_chunk(&self, chunk_index: u64) -> Result<Arc<Vec<u8>>, AppError> {
-        let (tx, rx) = std::sync::mpsc::channel();
-        let client = self.client.clone();
-        let url = self.url.clone();
-
-        get_rt().spawn(async move {
-            let start = chunk_index * CHUNK_SIZE as u64;
-            let range_header = format!("bytes={}-{}", start, start + CHUNK_SIZE as u64 - 1);
-
-            let result = async {
-                let response = client.get(&url).header(RANGE, range_header).send().await?;
-                if response.status().is_success() || response.status().as_u16() == 206 {
-                    Ok(Arc::new(response.bytes().await?.to_vec()))
-                } else {
-                    Err(AppError::Network(format!(
-                        "HTTP {} for chunk {}",
-                        response.status(),
-                        chunk_index
-                    )))
-                }
-            }
-            .await;
+    /// 已下载的字节范围，按起点排序并合并相邻的块
+    pub fn buffered_ranges(&self) -> Vec<Range<u64>> {
+        self.downloads.buffered_ranges()
+    }
 
-            let _ = tx.send(result);
-        });
+    /// 从当前读取位置起连续可读的数据末尾
+    pub fn buffered_end(&self) -> u64 {
+        let position = self.position.lock().map_or(0, |position| *position);
+        self.buffered_ranges()
+            .into_iter()
+            .find(|range| range.start <= position && position <= range.end)
+            .map_or(position, |range| range.end)
+    }
 
-        rx.recv()?
+    /// 连续缓冲到的位置占总长度的百分比，长度未知时为 `None`
+    pub fn buffered_percent(&self) -> Option<f32> {
+        let length = self.len()?;
+        Some((self.buffered_end() as f64 / length as f64 * 100.0).min(100.0) as f32)
     }
 
-    fn get_chunk(&self, chunk_index: u64) -> Result<Arc<Vec<u8>>, AppError> {
-        if let Some(chunk) = self.chunks.lock()?.get(&chunk_index) {
-            return Ok(Arc::clone(chunk));
-        }
+    pub fn wait_preload(&self, chunk_count: u64) -> Result<(), AppError> {
+        let start_chunk = *self.position.lock()? / CHUNK_SIZE as u64;
+        debug!("Preloading {} data chunks...", chunk_count);
 
-        let chunk_data = self.fetch_chunk(chunk_index)?;
-        let mut chunks = self.chunks.lock()?;
-
-        if chunks.len() >= MAX_CACHED_CHUNKS {
-            let current_chunk = *self.position.lock()? / CHUNK_SIZE as u64;
-            let keep_range = current_chunk.saturating_sub(2)..=current_chunk + 6;
-
-            let old_len = chunks.len();
-            chunks.retain(|&k, _| keep_range.contains(&k));
-
-            while chunks.len() >= MAX_CACHED_CHUNKS {
-                if let Some(&farthest_key) = chunks.keys().max_by_key(|&&k| {
-                    if k > current_chunk {
-                        k - current_chunk
-                    } else {
-                        current_chunk - k
-                    }
-                }) {
-                    chunks.remove(&farthest_key);
-                } else {
-                    break;
-                }
+        // 先全部排队，让调度器并发下载，再逐个等待
+        self.downloads
+            .prefetch(start_chunk..start_chunk + chunk_count);
+        for index in start_chunk..start_chunk + chunk_count {
+            if let Err(e) = self.downloads.get(index) {
+                debug!("Failed to preload chunk {}: {}", index, e);
+                break;
             }
-
-            debug!(
-                "Cache cleanup: reduced from {} to {} data chunks",
-                old_len,
-                chunks.len()
-            );
         }
-
-        chunks.insert(chunk_index, Arc::clone(&chunk_data));
-        Ok(chunk_data)
+        if self.len().is_none() {
+            self.downloads.probe_length()?;
+        }
+        Ok(())
     }
 }
 
@@ -145,16 +72,28 @@ impl Read for Reader {
             .position
             .lock()
             .map_err(|e| std::io::Error::other(e.to_string()))?;
+        // 已到末尾，不再请求超出范围的数据
+        if self.len().is_some_and(|length| position >= length) {
+            return Ok(0);
+        }
         let chunk_index = position / CHUNK_SIZE as u64;
         let chunk_offset = (position % CHUNK_SIZE as u64) as usize;
 
         let chunk_data = self
-            .get_chunk(chunk_index)
+            .downloads
+            .get(chunk_index)
             .map_err(|e| std::io::Error::other(format!("Failed to fetch chunk: {}", e)))?;
 
         let available = chunk_data.len().saturating_sub(chunk_offset);
         if available == 0 {
-            return Ok(0);
+            // 总长度已知却提前没有数据，说明连接中途断开
+            return match self.len() {
+                Some(length) => Err(std::io::Error::other(format!(
+                    "Stream truncated at {} of {} bytes",
+                    position, length
+                ))),
+                None => Ok(0),
+            };
         }
 
         let to_read = std::cmp::min(buf.len(), available);
@@ -166,15 +105,7 @@ impl Read for Reader {
             .map_err(|e| std::io::Error::other(e.to_string()))? += to_read as u64;
 
         if chunk_offset + to_read > CHUNK_SIZE * 2 / 3 {
-            let self_clone = self.clone();
-            let next_chunk = chunk_index + 1;
-            std::thread::spawn(move || {
-                for i in 0..2 {
-                    if self_clone.get_chunk(next_chunk + i).is_err() {
-                        break;
-                    }
-                }
-            });
+            self.downloads.prefetch(chunk_index + 1..chunk_index + 3);
         }
 
         Ok(to_read)
@@ -191,7 +122,12 @@ impl Seek for Reader {
         let new_position = match pos {
             SeekFrom::Start(offset) => offset,
             SeekFrom::Current(offset) => (current as i64 + offset).max(0) as u64,
-            SeekFrom::End(offset) => (current as i64 + offset).max(0) as u64,
+            SeekFrom::End(offset) => {
+                let length = self.len().ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::Unsupported, "stream length is unknown")
+                })?;
+                (length as i64 + offset).max(0) as u64
+            }
         };
 
         *self
@@ -199,16 +135,74 @@ impl Seek for Reader {
             .lock()
             .map_err(|e| std::io::Error::other(e.to_string()))? = new_position;
 
-        let chunk_index = new_position / CHUNK_SIZE as u64;
-        let self_clone = self.clone();
-        std::thread::spawn(move || {
-            for i in chunk_index.saturating_sub(3)..=chunk_index + 3 {
-                if self_clone.get_chunk(i).is_err() {
-                    break;
-                }
-            }
-        });
+        self.downloads.seek(new_position / CHUNK_SIZE as u64);
 
         Ok(new_position)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+
+    #[test]
+    fn seeks_from_end_once_length_is_known() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(CHUNK_SIZE + 1000).collect();
+        let length = data.len() as u64;
+        let server = MockServer::file(data.clone());
+        let mut reader = Reader::new(server.url().to_string());
+
+        let error = reader.seek(SeekFrom::End(-10)).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+
+        reader.wait_preload(1).unwrap();
+        assert_eq!(reader.len(), Some(length));
+        assert_eq!(reader.seek(SeekFrom::End(-10)).unwrap(), length - 10);
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, data[data.len() - 10..]);
+
+        // 超出末尾时读不到数据，超出开头时停在开头
+        assert_eq!(reader.seek(SeekFrom::End(5)).unwrap(), length + 5);
+        assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+        assert_eq!(
+            reader.seek(SeekFrom::End(-(length as i64) - 10)).unwrap(),
+            0
+        );
+        let mut head = [0; 4];
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(head, data[..4]);
+    }
+
+    #[test]
+    fn buffered_ranges_outlive_evicted_chunks() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(CHUNK_SIZE * 12 + 100).collect();
+        let length = data.len() as u64;
+        let server = MockServer::file(data);
+        let mut reader = Reader::new(server.url().to_string());
+        assert_eq!(reader.buffered_percent(), None);
+
+        reader.seek(SeekFrom::Start(CHUNK_SIZE as u64 * 4)).unwrap();
+        reader.read_exact(&mut [0; 16]).unwrap();
+        let chunk = CHUNK_SIZE as u64;
+        let ranges = reader.buffered_ranges();
+        assert!(
+            ranges
+                .iter()
+                .any(|range| range.start <= chunk * 4 && chunk * 5 <= range.end),
+            "{:?}",
+            ranges
+        );
+        assert!(ranges.iter().all(|range| range.start >= chunk * 3));
+
+        // 读完整个文件时缓存早已清理过，已下载的范围仍然完整
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(reader.buffered_ranges(), vec![0..length]);
+        reader.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(reader.buffered_end(), length);
+        assert_eq!(reader.buffered_percent(), Some(100.0));
+    }
+}
@@ -0,0 +1,136 @@
+use log::debug;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+use crate::error::AppError;
+use crate::models::audio::QueueSource;
+use crate::models::song::Song;
+use crate::service::{dj, recommend, similar};
+
+/// 电台每次请求的节目数
+const PROGRAM_PAGE: u32 = 20;
+/// 歌曲电台每批最多加入的歌曲数
+const RADIO_BATCH: usize = 10;
+/// 歌曲电台单次续播最多尝试的种子数
+const RADIO_ATTEMPTS: usize = 5;
+
+static SESSION_HEARD: OnceLock<Mutex<HashSet<u64>>> = OnceLock::new();
+
+fn session_heard() -> &'static Mutex<HashSet<u64>> {
+    SESSION_HEARD.get_or_init(Default::default)
+}
+
+/// 记录本次运行中已经播放过的歌曲
+pub fn mark_heard(id: u64) {
+    if let Ok(mut heard) = session_heard().lock() {
+        heard.insert(id);
+    }
+}
+
+fn is_heard(id: u64) -> bool {
+    session_heard()
+        .lock()
+        .map(|heard| heard.contains(&id))
+        .unwrap_or(false)
+}
+
+/// 记录某个 [`QueueSource`] 的续播进度
+pub struct SourceCursor {
+    source: QueueSource,
+    /// 已经交给播放器的歌曲，避免来源返回重复的歌曲
+    seen: HashSet<u64>,
+    last_id: Option<u64>,
+    /// 分页来源已请求的数量
+    offset: u32,
+    /// 分页来源已没有更多内容
+    exhausted: bool,
+    /// 歌曲电台待展开的种子，每批结果会作为新的种子加入
+    seeds: VecDeque<u64>,
+}
+
+impl SourceCursor {
+    pub fn new(source: QueueSource) -> Self {
+        let mut seen = HashSet::new();
+        let mut seeds = VecDeque::new();
+        if let QueueSource::SongRadio { song_id } = source {
+            seen.insert(song_id);
+            seeds.push_back(song_id);
+        }
+
+        Self {
+            source,
+            seen,
+            last_id: None,
+            offset: 0,
+            exhausted: false,
+            seeds,
+        }
+    }
+
+    pub fn source(&self) -> &QueueSource {
+        &self.source
+    }
+
+    pub async fn next_batch(&mut self) -> Result<Vec<Song>, AppError> {
+        if self.exhausted {
+            return Ok(Vec::new());
+        }
+
+        let songs = match self.source {
+            QueueSource::PersonalFm => recommend::get_personal_fm().await?,
+            QueueSource::Intelligence {
+                song_id,
+                playlist_id,
+            } => {
+                let start_id = self.last_id.unwrap_or(song_id);
+                recommend::get_intelligence_list(song_id, playlist_id, start_id).await?
+            }
+            QueueSource::DjRadio { radio_id, sort } => {
+                let page = dj::get_programs(radio_id, sort, PROGRAM_PAGE, self.offset).await?;
+                self.offset += page.programs.len() as u32;
+                self.exhausted = !page.has_more || page.programs.is_empty();
+                page.programs.into_iter().map(|p| p.main_song).collect()
+            }
+            QueueSource::SongRadio { .. } => return self.next_radio_batch().await,
+        };
+
+        let fresh: Vec<Song> = songs
+            .into_iter()
+            .filter(|song| self.seen.insert(song.id))
+            .collect();
+        if let Some(last) = fresh.last() {
+            self.last_id = Some(last.id);
+        }
+
+        debug!("{:?} supplied {} new track(s)", self.source, fresh.len());
+        Ok(fresh)
+    }
+
+    /// 依次展开种子的相似歌曲，跳过本次运行中已经听过的
+    async fn next_radio_batch(&mut self) -> Result<Vec<Song>, AppError> {
+        for _ in 0..RADIO_ATTEMPTS {
+            let Some(seed) = self.seeds.pop_front() else {
+                break;
+            };
+
+            let fresh: Vec<Song> = similar::get_similar_songs(seed, 50, 0)
+                .await?
+                .into_iter()
+                .filter(|song| !is_heard(song.id) && !self.seen.contains(&song.id))
+                .take(RADIO_BATCH)
+                .collect();
+            for song in &fresh {
+                self.seen.insert(song.id);
+                self.seeds.push_back(song.id);
+            }
+
+            if !fresh.is_empty() {
+                debug!("Song radio expanded {} into {} track(s)", seed, fresh.len());
+                return Ok(fresh);
+            }
+        }
+
+        self.exhausted = self.seeds.is_empty();
+        Ok(Vec::new())
+    }
+}
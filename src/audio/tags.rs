@@ -0,0 +1,224 @@
+use log::debug;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::models::audio::ReplayGain;
+
+/// 标签区超过该大小时放弃读取，通常是内嵌了很大的封面
+const MAX_TAG_SIZE: usize = 1024 * 1024;
+
+/// 读取文件开头的 ReplayGain 或 R128 标签，读取后回到文件开头
+///
+/// 支持 MP3 的 ID3v2 `TXXX` 帧与 FLAC 的 Vorbis 注释
+pub fn read_replay_gain<R: Read + Seek>(reader: &mut R) -> Option<ReplayGain> {
+    let result = read_tags(reader);
+    if let Err(e) = reader.seek(SeekFrom::Start(0)) {
+        debug!("Failed to rewind after reading tags: {}", e);
+    }
+
+    match result {
+        Ok(tags) => from_tags(&tags),
+        Err(e) => {
+            debug!("Failed to read tags: {}", e);
+            None
+        }
+    }
+}
+
+fn read_tags<R: Read + Seek>(reader: &mut R) -> io::Result<Vec<(String, String)>> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    match &magic {
+        [b'I', b'D', b'3', _] => read_id3(reader, magic[3]),
+        b"fLaC" => read_flac(reader),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn read_block<R: Read>(reader: &mut R, size: usize) -> io::Result<Vec<u8>> {
+    if size > MAX_TAG_SIZE {
+        return Err(io::Error::other(format!("tag too large: {} bytes", size)));
+    }
+    let mut data = vec![0u8; size];
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+fn syncsafe(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0, |size, byte| (size << 7) | usize::from(byte & 0x7f))
+}
+
+fn big_endian(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0, |size, byte| (size << 8) | usize::from(*byte))
+}
+
+fn read_id3<R: Read>(reader: &mut R, version: u8) -> io::Result<Vec<(String, String)>> {
+    // 已读取 "ID3" 与主版本号，剩余的头部为修订号、标志与大小
+    let mut header = [0u8; 6];
+    reader.read_exact(&mut header)?;
+    let flags = header[1];
+    let tag = read_block(reader, syncsafe(&header[2..6]))?;
+
+    let mut offset = 0;
+    if flags & 0x40 != 0 && version >= 3 {
+        // 扩展头部，v2.4 的大小包含自身
+        let size = tag.get(0..4).map_or(0, |bytes| match version {
+            4 => syncsafe(bytes),
+            _ => big_endian(bytes) + 4,
+        });
+        offset += size;
+    }
+
+    let (id_len, header_len) = if version == 2 { (3, 6) } else { (4, 10) };
+    let mut pairs = Vec::new();
+    while offset + header_len <= tag.len() {
+        let frame = &tag[offset..offset + header_len];
+        if frame[0] == 0 {
+            break;
+        }
+        let size_bytes = &frame[id_len..id_len + if version == 2 { 3 } else { 4 }];
+        let size = match version {
+            4 => syncsafe(size_bytes),
+            _ => big_endian(size_bytes),
+        };
+        let start = offset + header_len;
+        let Some(body) = tag.get(start..start + size) else {
+            break;
+        };
+        if &frame[..id_len] == b"TXXX" || &frame[..id_len] == b"TXX" {
+            pairs.extend(parse_txxx(body));
+        }
+        offset = start + size;
+    }
+    Ok(pairs)
+}
+
+/// 按 ID3 的文本编码解码，返回文本与结束符之后的位置
+fn decode_text(encoding: u8, data: &[u8]) -> (String, usize) {
+    let wide = matches!(encoding, 1 | 2);
+    let end = if wide {
+        data.chunks_exact(2)
+            .position(|pair| pair == [0, 0])
+            .map_or(data.len(), |i| i * 2)
+    } else {
+        data.iter().position(|b| *b == 0).unwrap_or(data.len())
+    };
+    let next = (end + if wide { 2 } else { 1 }).min(data.len());
+    let bytes = &data[..end];
+
+    let text = match encoding {
+        0 => bytes.iter().map(|b| char::from(*b)).collect(),
+        1 | 2 => {
+            let (big, bytes) = match bytes {
+                [0xfe, 0xff, rest @ ..] => (true, rest),
+                [0xff, 0xfe, rest @ ..] => (false, rest),
+                _ => (encoding == 2, bytes),
+            };
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| match big {
+                    true => u16::from_be_bytes([pair[0], pair[1]]),
+                    false => u16::from_le_bytes([pair[0], pair[1]]),
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    };
+    (text, next)
+}
+
+fn parse_txxx(body: &[u8]) -> Option<(String, String)> {
+    let (&encoding, rest) = body.split_first()?;
+    let (description, next) = decode_text(encoding, rest);
+    let (value, _) = decode_text(encoding, &rest[next..]);
+    Some((description, value))
+}
+
+fn read_flac<R: Read + Seek>(reader: &mut R) -> io::Result<Vec<(String, String)>> {
+    loop {
+        let mut header = [0u8; 4];
+        reader.read_exact(&mut header)?;
+        let last = header[0] & 0x80 != 0;
+        let size = big_endian(&header[1..4]);
+
+        // 4 为 VORBIS_COMMENT
+        if header[0] & 0x7f == 4 {
+            return Ok(parse_vorbis_comments(&read_block(reader, size)?));
+        }
+        if last {
+            return Ok(Vec::new());
+        }
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+}
+
+fn parse_vorbis_comments(data: &[u8]) -> Vec<(String, String)> {
+    let read_u32 = |offset: usize| -> Option<usize> {
+        let bytes = data.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?) as usize)
+    };
+
+    let mut pairs = Vec::new();
+    let Some(vendor) = read_u32(0) else {
+        return pairs;
+    };
+    let mut offset = 4 + vendor;
+    let count = read_u32(offset).unwrap_or(0);
+    offset += 4;
+
+    for _ in 0..count {
+        let Some(len) = read_u32(offset) else {
+            break;
+        };
+        let Some(comment) = data.get(offset + 4..offset + 4 + len) else {
+            break;
+        };
+        if let Some((key, value)) = String::from_utf8_lossy(comment).split_once('=') {
+            pairs.push((key.to_string(), value.to_string()));
+        }
+        offset += 4 + len;
+    }
+    pairs
+}
+
+/// 解析 "-6.52 dB" 这样的数值
+fn parse_number(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.')))
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// R128 增益为 Q7.8 定点数，以 -23 LUFS 为参考，换算到 ReplayGain 的 -18 LUFS
+fn parse_r128(value: &str) -> Option<f32> {
+    let gain: i32 = value.trim().parse().ok()?;
+    Some(gain as f32 / 256.0 + 5.0)
+}
+
+fn from_tags(tags: &[(String, String)]) -> Option<ReplayGain> {
+    let mut track_gain = None;
+    let mut album_gain = None;
+    let mut gain = ReplayGain::default();
+
+    for (key, value) in tags {
+        match key.to_ascii_uppercase().as_str() {
+            "REPLAYGAIN_TRACK_GAIN" => track_gain = parse_number(value).or(track_gain),
+            "REPLAYGAIN_ALBUM_GAIN" => album_gain = parse_number(value).or(album_gain),
+            "REPLAYGAIN_TRACK_PEAK" => gain.track_peak = parse_number(value),
+            "REPLAYGAIN_ALBUM_PEAK" => gain.album_peak = parse_number(value),
+            "R128_TRACK_GAIN" => track_gain = track_gain.or(parse_r128(value)),
+            "R128_ALBUM_GAIN" => album_gain = album_gain.or(parse_r128(value)),
+            _ => {}
+        }
+    }
+
+    gain.track_gain = track_gain.or(album_gain)?;
+    gain.album_gain = album_gain;
+    debug!("Found ReplayGain tags: {:?}", gain);
+    Some(gain)
+}
@@ -0,0 +1,392 @@
+use log::{debug, error};
+use slint::{ComponentHandle, ModelRc, VecModel, Weak};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+use crate::audio::engine::get_backend;
+use crate::controller::playlist::{format_duration, to_tracks};
+use crate::controller::profile::format_date;
+use crate::error::AppError;
+use crate::models::album::{Album, AlbumDetail, AlbumDynamic};
+use crate::models::artist::{Artist, ArtistDescription, ArtistDetail, ArtistMv};
+use crate::models::audio::{BackendState, PlayOrigin};
+use crate::service::{album, artist};
+use crate::{AlbumInfo, ArtistInfo, EntryItem, MainWindow};
+
+/// 关注与收藏列表只展示第一页
+const LIBRARY_LIMIT: u32 = 100;
+const PAGE_SIZE: u32 = 30;
+
+fn send(command: BackendState) {
+    if let Err(e) = get_backend().command_sender.send(command) {
+        error!("Failed to send audio command: {}", e);
+    }
+}
+
+pub fn to_entries<T>(items: &[T], entry: impl Fn(&T) -> EntryItem) -> ModelRc<EntryItem> {
+    ModelRc::new(VecModel::from(items.iter().map(entry).collect::<Vec<_>>()))
+}
+
+pub fn artist_entry(artist: &Artist) -> EntryItem {
+    EntryItem {
+        id: artist.id.to_string().into(),
+        name: artist.name.as_str().into(),
+        detail: format!("{} 张专辑", artist.album_size).into(),
+    }
+}
+
+fn album_entry(album: &Album) -> EntryItem {
+    EntryItem {
+        id: album.id.to_string().into(),
+        name: album.name.as_str().into(),
+        detail: format!("{}  {} 首", format_date(album.publish_time), album.size).into(),
+    }
+}
+
+fn mv_entry(mv: &ArtistMv) -> EntryItem {
+    EntryItem {
+        id: mv.id.to_string().into(),
+        name: mv.name.as_str().into(),
+        detail: format!(
+            "{}  {}  播放 {}",
+            mv.publish_time.as_deref().unwrap_or_default(),
+            format_duration(mv.duration),
+            mv.play_count
+        )
+        .into(),
+    }
+}
+
+fn to_artist_info(detail: &ArtistDetail, description: &ArtistDescription) -> ArtistInfo {
+    let artist = &detail.artist;
+    let alias: Vec<&str> = artist
+        .trans
+        .iter()
+        .chain(&artist.alias)
+        .map(String::as_str)
+        .filter(|a| !a.is_empty())
+        .collect();
+    let sections = description
+        .sections
+        .iter()
+        .map(|s| format!("{}\n{}", s.title, s.text));
+    // 百科没有简介时使用歌手信息中的简介
+    let brief = Some(description.brief.clone())
+        .filter(|b| !b.trim().is_empty())
+        .or_else(|| artist.brief_desc.clone())
+        .unwrap_or_default();
+    let description: Vec<String> = std::iter::once(brief)
+        .chain(sections)
+        .filter(|s| !s.trim().is_empty())
+        .collect();
+
+    ArtistInfo {
+        id: artist.id.to_string().into(),
+        name: artist.name.as_str().into(),
+        alias: alias.join(" / ").into(),
+        counts: format!(
+            "{} 首歌曲  {} 张专辑  {} 个MV",
+            artist.music_size, artist.album_size, artist.mv_size
+        )
+        .into(),
+        description: description.join("\n\n").into(),
+        followed: artist.followed,
+    }
+}
+
+fn to_album_info(detail: &AlbumDetail, dynamic: &AlbumDynamic) -> AlbumInfo {
+    let album = &detail.album;
+    let mut info = vec![format_date(album.publish_time)];
+    info.extend(album.album_type.clone().filter(|t| !t.is_empty()));
+    info.extend(album.company.clone().filter(|c| !c.is_empty()));
+    info.push(format!("{} 首", album.size));
+
+    AlbumInfo {
+        id: album.id.to_string().into(),
+        name: album.name.as_str().into(),
+        artist_id: album.artist.id.to_string().into(),
+        artist: if album.artists.is_empty() {
+            album.artist.name.as_str().into()
+        } else {
+            let names: Vec<&str> = album.artists.iter().map(|a| a.name.as_str()).collect();
+            names.join(" / ").into()
+        },
+        detail: info.join("  ").into(),
+        stats: format!(
+            "收藏 {}  点赞 {}  评论 {}  分享 {}",
+            dynamic.sub_count, dynamic.liked_count, dynamic.comment_count, dynamic.share_count
+        )
+        .into(),
+        description: album.description.as_deref().unwrap_or_default().into(),
+        subscribed: dynamic.is_sub,
+    }
+}
+
+#[derive(Default)]
+struct ArtistState {
+    artist: Option<ArtistDetail>,
+    albums: Vec<Album>,
+    mvs: Vec<ArtistMv>,
+    album: Option<AlbumDetail>,
+}
+
+/// 歌手与专辑页面
+#[derive(Clone)]
+pub struct ArtistController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    state: Arc<Mutex<ArtistState>>,
+}
+
+impl ArtistController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            state: Arc::default(),
+        };
+
+        window.on_load_artist_library({
+            let controller = controller.clone();
+            move || controller.load()
+        });
+        window.on_open_artist({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.trim().parse() {
+                    controller.open_artist(id);
+                }
+            }
+        });
+        window.on_open_album({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.trim().parse() {
+                    controller.open_album(id);
+                }
+            }
+        });
+        window.on_follow_artist({
+            let controller = controller.clone();
+            move |id, follow| {
+                if let Ok(id) = id.parse() {
+                    controller.follow(id, follow);
+                }
+            }
+        });
+        window.on_subscribe_album({
+            let controller = controller.clone();
+            move |id, subscribe| {
+                if let Ok(id) = id.parse() {
+                    controller.subscribe(id, subscribe);
+                }
+            }
+        });
+        window.on_load_more_artist_albums({
+            let controller = controller.clone();
+            move || controller.load_more_albums()
+        });
+        window.on_load_more_artist_mvs({
+            let controller = controller.clone();
+            move || controller.load_more_mvs()
+        });
+        window.on_play_artist({
+            let controller = controller.clone();
+            move |index| {
+                let Some(detail) = controller.lock().artist.clone() else {
+                    return;
+                };
+                let origin = PlayOrigin::Artist(detail.artist.id);
+                send(BackendState::Replace(
+                    detail.hot_songs,
+                    origin,
+                    index.max(0) as usize,
+                ));
+            }
+        });
+        window.on_play_album({
+            let controller = controller.clone();
+            move |index| {
+                let Some(detail) = controller.lock().album.clone() else {
+                    return;
+                };
+                let origin = PlayOrigin::Album(detail.album.id);
+                send(BackendState::Replace(
+                    detail.songs,
+                    origin,
+                    index.max(0) as usize,
+                ));
+            }
+        });
+
+        controller
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ArtistState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 在后台执行操作，失败时显示错误
+    fn run<F>(&self, action: &'static str, task: F)
+    where
+        F: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        self.set_status(true, "");
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            match task.await {
+                Ok(()) => controller.set_status(false, ""),
+                Err(e) => {
+                    error!("Failed to {}: {}", action, e);
+                    controller.set_status(false, &format!("操作失败: {}", e));
+                }
+            }
+        });
+    }
+
+    /// 加载关注的歌手与收藏的专辑
+    pub fn load(&self) {
+        let window = self.window.clone();
+        self.run("load followed artists", async move {
+            let (artists, albums) = tokio::try_join!(
+                artist::get_followed_artists(LIBRARY_LIMIT, 0),
+                album::get_subscribed_albums(LIBRARY_LIMIT, 0),
+            )?;
+            let _ = window.upgrade_in_event_loop(move |w| {
+                w.set_followed_artists(to_entries(&artists, artist_entry));
+                w.set_subscribed_albums(to_entries(&albums, album_entry));
+            });
+            Ok(())
+        });
+    }
+
+    pub fn open_artist(&self, id: u64) {
+        let controller = self.clone();
+        self.run("load artist", async move {
+            let (detail, description, albums, mvs) = tokio::try_join!(
+                artist::get_artist(id),
+                artist::get_description(id),
+                artist::get_albums(id, PAGE_SIZE, 0),
+                artist::get_mvs(id, PAGE_SIZE, 0),
+            )?;
+            // 相似歌手需要登录，失败时不影响其他内容
+            let similar = artist::get_similar_artists(id)
+                .await
+                .inspect_err(|e| debug!("No similar artists for {}: {}", id, e))
+                .unwrap_or_default();
+
+            let info = to_artist_info(&detail, &description);
+            let tracks = detail.hot_songs.clone();
+            {
+                let mut state = controller.lock();
+                state.artist = Some(detail);
+                state.albums = albums.albums.clone();
+                state.mvs = mvs.mvs.clone();
+            }
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_artist(info);
+                w.set_artist_tracks(to_tracks(&tracks));
+                w.set_artist_albums(to_entries(&albums.albums, album_entry));
+                w.set_artist_albums_has_more(albums.has_more);
+                w.set_artist_mvs(to_entries(&mvs.mvs, mv_entry));
+                w.set_artist_mvs_has_more(mvs.has_more);
+                w.set_similar_artists(to_entries(&similar, artist_entry));
+                w.set_album_view(false);
+            });
+            Ok(())
+        });
+    }
+
+    pub fn open_album(&self, id: u64) {
+        let controller = self.clone();
+        self.run("load album", async move {
+            let (detail, dynamic) =
+                tokio::try_join!(album::get_album(id), album::get_album_dynamic(id))?;
+
+            let info = to_album_info(&detail, &dynamic);
+            let tracks = detail.songs.clone();
+            controller.lock().album = Some(detail);
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_album(info);
+                w.set_album_tracks(to_tracks(&tracks));
+                w.set_album_view(true);
+            });
+            Ok(())
+        });
+    }
+
+    fn follow(&self, id: u64, follow: bool) {
+        let controller = self.clone();
+        self.run("follow artist", async move {
+            artist::follow_artist(id, follow).await?;
+            controller.open_artist(id);
+            controller.load();
+            Ok(())
+        });
+    }
+
+    fn subscribe(&self, id: u64, subscribe: bool) {
+        let controller = self.clone();
+        self.run("subscribe album", async move {
+            album::subscribe_album(id, subscribe).await?;
+            controller.open_album(id);
+            controller.load();
+            Ok(())
+        });
+    }
+
+    fn load_more_albums(&self) {
+        let Some((id, offset)) = self.lock_page(|state| state.albums.len()) else {
+            return;
+        };
+        let controller = self.clone();
+        self.run("load artist albums", async move {
+            let page = artist::get_albums(id, PAGE_SIZE, offset).await?;
+            let albums = {
+                let mut state = controller.lock();
+                state.albums.extend(page.albums);
+                state.albums.clone()
+            };
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_artist_albums(to_entries(&albums, album_entry));
+                w.set_artist_albums_has_more(page.has_more);
+            });
+            Ok(())
+        });
+    }
+
+    fn load_more_mvs(&self) {
+        let Some((id, offset)) = self.lock_page(|state| state.mvs.len()) else {
+            return;
+        };
+        let controller = self.clone();
+        self.run("load artist mvs", async move {
+            let page = artist::get_mvs(id, PAGE_SIZE, offset).await?;
+            let mvs = {
+                let mut state = controller.lock();
+                state.mvs.extend(page.mvs);
+                state.mvs.clone()
+            };
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_artist_mvs(to_entries(&mvs, mv_entry));
+                w.set_artist_mvs_has_more(page.has_more);
+            });
+            Ok(())
+        });
+    }
+
+    /// 当前歌手的ID与已加载的数量
+    fn lock_page(&self, loaded: impl Fn(&ArtistState) -> usize) -> Option<(u64, u32)> {
+        let state = self.lock();
+        let id = state.artist.as_ref()?.artist.id;
+        Some((id, loaded(&state) as u32))
+    }
+
+    fn set_status(&self, loading: bool, status: &str) {
+        let status = status.to_string();
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_artist_loading(loading);
+            w.set_artist_status(status.into());
+        });
+    }
+}
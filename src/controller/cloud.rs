@@ -0,0 +1,269 @@
+use log::error;
+use slint::{ComponentHandle, ModelRc, VecModel, Weak};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+use crate::audio::engine::get_backend;
+use crate::error::AppError;
+use crate::models::audio::{BackendState, PlayOrigin};
+use crate::models::cloud::{CloudPage, CloudSong, CloudSongMeta};
+use crate::service::auth::get_login_uid;
+use crate::service::cloud::CloudDrive;
+use crate::{CloudItem, MainWindow};
+
+const PAGE_SIZE: u32 = 100;
+
+fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= 1024.0 * MB {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * MB))
+    } else {
+        format!("{:.1} MB", bytes as f64 / MB)
+    }
+}
+
+fn to_items(songs: &[CloudSong]) -> ModelRc<CloudItem> {
+    let items: Vec<CloudItem> = songs
+        .iter()
+        .map(|s| CloudItem {
+            id: s.song_id.to_string().into(),
+            name: if s.song_name.is_empty() {
+                s.file_name.as_str().into()
+            } else {
+                s.song_name.as_str().into()
+            },
+            artist: s.artist.as_str().into(),
+            album: s.album.as_str().into(),
+            detail: format!("{}  {} kbps", format_size(s.file_size), s.bitrate / 1000).into(),
+        })
+        .collect();
+    ModelRc::new(VecModel::from(items))
+}
+
+/// 留空的字段交给服务端按文件名填写
+fn non_empty(text: &str) -> Option<String> {
+    Some(text.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// 云盘页面：歌曲列表、上传、删除与匹配
+#[derive(Clone)]
+pub struct CloudController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    drive: Arc<CloudDrive>,
+    /// 已加载的云盘歌曲，加载更多时追加
+    songs: Arc<Mutex<Vec<CloudSong>>>,
+}
+
+impl CloudController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            drive: Arc::new(CloudDrive::default()),
+            songs: Arc::default(),
+        };
+
+        window.on_load_cloud({
+            let controller = controller.clone();
+            move || controller.load(false)
+        });
+        window.on_load_more_cloud({
+            let controller = controller.clone();
+            move || controller.load(true)
+        });
+        window.on_upload_cloud({
+            let controller = controller.clone();
+            move |path, song, artist, album| {
+                let meta = CloudSongMeta {
+                    song: non_empty(&song),
+                    artist: non_empty(&artist),
+                    album: non_empty(&album),
+                    bitrate: None,
+                };
+                controller.upload(PathBuf::from(path.trim()), meta);
+            }
+        });
+        window.on_play_cloud({
+            let controller = controller.clone();
+            move |index| controller.play(index.max(0) as usize)
+        });
+        window.on_delete_cloud({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.parse() {
+                    controller.delete(id);
+                }
+            }
+        });
+        window.on_match_cloud({
+            let controller = controller.clone();
+            move |id, official_id| {
+                if let (Ok(id), Ok(official_id)) = (id.parse(), official_id.trim().parse()) {
+                    controller.match_song(id, official_id);
+                }
+            }
+        });
+
+        controller
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<CloudSong>> {
+        self.songs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 在后台执行操作，失败时显示错误
+    fn run<F>(&self, action: &'static str, task: F)
+    where
+        F: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = task.await {
+                error!("Failed to {}: {}", action, e);
+                controller.set_status(false, &format!("操作失败: {}", e));
+            }
+        });
+    }
+
+    /// 加载云盘歌曲，`more` 为 `true` 时接着已加载的部分继续
+    pub fn load(&self, more: bool) {
+        self.set_status(true, "");
+        let offset = if more { self.lock().len() as u32 } else { 0 };
+
+        let controller = self.clone();
+        self.run("load cloud songs", async move {
+            let page = controller.drive.list(PAGE_SIZE, offset).await?;
+            controller.show(page, more);
+            Ok(())
+        });
+    }
+
+    fn show(&self, page: CloudPage, more: bool) {
+        let songs = {
+            let mut songs = self.lock();
+            if !more {
+                songs.clear();
+            }
+            songs.extend(page.songs);
+            songs.clone()
+        };
+        let summary = format!(
+            "{} 首，已用 {} / {}",
+            page.count,
+            format_size(page.size),
+            format_size(page.max_size)
+        );
+
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_cloud_songs(to_items(&songs));
+            w.set_cloud_summary(summary.into());
+            w.set_cloud_has_more(page.has_more);
+            w.set_cloud_loading(false);
+            w.set_cloud_status("".into());
+        });
+    }
+
+    fn upload(&self, path: PathBuf, meta: CloudSongMeta) {
+        let _ = self.window.upgrade_in_event_loop(|w| {
+            w.set_cloud_uploading(true);
+            w.set_cloud_status("正在计算文件校验值...".into());
+        });
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            // 进度回调跨越 await，需要 Sync
+            let window = Mutex::new(controller.window.clone());
+            let last_percent = AtomicU64::new(u64::MAX);
+            let result = controller
+                .drive
+                .upload(&path, meta, |done, total| {
+                    let percent = (done * 100).checked_div(total).unwrap_or(0);
+                    if last_percent.swap(percent, Ordering::Relaxed) == percent {
+                        return;
+                    }
+                    if let Ok(window) = window.lock() {
+                        let _ = window.upgrade_in_event_loop(move |w| {
+                            w.set_cloud_status(format!("上传中 {}%", percent).into());
+                        });
+                    }
+                })
+                .await;
+
+            let status = match result {
+                Ok(_) => {
+                    controller.load(false);
+                    format!("已上传 {}", path.display())
+                }
+                Err(e) => {
+                    error!("Failed to upload {}: {}", path.display(), e);
+                    format!("上传失败，再次上传会从中断处继续: {}", e)
+                }
+            };
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_cloud_uploading(false);
+                w.set_cloud_status(status.into());
+            });
+        });
+    }
+
+    /// 从选中的歌曲开始播放列表中的全部云盘歌曲
+    fn play(&self, index: usize) {
+        let loaded = self.lock();
+        let Some(selected) = loaded.get(index) else {
+            return;
+        };
+        let songs: Vec<_> = loaded
+            .iter()
+            .filter_map(|s| s.simple_song.clone())
+            .collect();
+        let Some(start) = songs.iter().position(|s| {
+            selected
+                .simple_song
+                .as_ref()
+                .is_some_and(|song| song.id == s.id)
+        }) else {
+            return;
+        };
+        drop(loaded);
+
+        let command = BackendState::Replace(songs, PlayOrigin::Unknown, start);
+        if let Err(e) = get_backend().command_sender.send(command) {
+            error!("Failed to send audio command: {}", e);
+        }
+    }
+
+    fn delete(&self, id: u64) {
+        self.set_status(true, "");
+        let controller = self.clone();
+        self.run("delete cloud song", async move {
+            controller.drive.delete(&[id]).await?;
+            controller.load(false);
+            Ok(())
+        });
+    }
+
+    /// 关联到官方曲目，`official_id` 为 0 时取消关联
+    fn match_song(&self, id: u64, official_id: u64) {
+        self.set_status(true, "");
+        let controller = self.clone();
+        self.run("match cloud song", async move {
+            let uid = get_login_uid()
+                .await?
+                .ok_or_else(|| AppError::Common("Not logged in".to_string()))?;
+            controller.drive.match_song(uid, id, official_id).await?;
+            controller.load(false);
+            Ok(())
+        });
+    }
+
+    fn set_status(&self, loading: bool, status: &str) {
+        let status = status.to_string();
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_cloud_loading(loading);
+            w.set_cloud_status(status.into());
+        });
+    }
+}
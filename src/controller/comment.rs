@@ -0,0 +1,433 @@
+use log::error;
+use slint::{ComponentHandle, ModelRc, SharedString, VecModel, Weak};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::audio::engine::get_backend;
+use crate::models::audio::PlayerEvent;
+use crate::models::comment::{Comment, CommentPaging, CommentSort};
+use crate::models::resource::ResourceType;
+use crate::service::comment;
+use crate::{CommentItem, MainWindow};
+
+const FLOOR_PAGE_SIZE: u32 = 20;
+
+/// 展开的楼层回复
+struct FloorState {
+    parent_id: u64,
+    comments: Vec<Comment>,
+    cursor: Option<String>,
+    has_more: bool,
+}
+
+#[derive(Default)]
+struct CommentState {
+    target: Option<(ResourceType, u64)>,
+    paging: Option<CommentPaging>,
+    comments: Vec<Comment>,
+    floor: Option<FloorState>,
+}
+
+/// 正在播放页面的评论面板
+#[derive(Clone)]
+pub struct CommentController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    state: Arc<Mutex<CommentState>>,
+}
+
+fn to_item(comment: &Comment) -> CommentItem {
+    CommentItem {
+        id: comment.comment_id.to_string().into(),
+        nickname: comment.user.nickname.as_str().into(),
+        avatar_url: comment.user.avatar_url.as_str().into(),
+        content: comment.content.as_deref().unwrap_or("该评论已删除").into(),
+        time: comment.time_str.as_deref().unwrap_or_default().into(),
+        liked_count: comment.liked_count as i32,
+        liked: comment.liked,
+        reply_count: comment.reply_count() as i32,
+        reply_to: comment
+            .be_replied
+            .first()
+            .map(|r| r.user.nickname.as_str())
+            .unwrap_or_default()
+            .into(),
+    }
+}
+
+impl CommentController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            state: Arc::new(Mutex::new(CommentState::default())),
+        };
+
+        window.on_load_comments({
+            let controller = controller.clone();
+            move |sort| controller.reload(CommentSort::from_code(sort_code(sort)))
+        });
+        window.on_load_more_comments({
+            let controller = controller.clone();
+            move || controller.load_more()
+        });
+        window.on_post_comment({
+            let controller = controller.clone();
+            move |content| controller.post(None, content)
+        });
+        window.on_reply_comment({
+            let controller = controller.clone();
+            move |id, content| controller.post(id.parse().ok(), content)
+        });
+        window.on_like_comment({
+            let controller = controller.clone();
+            move |id, like| controller.like(id, like)
+        });
+        window.on_delete_comment({
+            let controller = controller.clone();
+            move |id| controller.delete(id)
+        });
+        window.on_toggle_comment_floor({
+            let controller = controller.clone();
+            move |id| controller.toggle_floor(id)
+        });
+        window.on_load_more_comment_floor({
+            let controller = controller.clone();
+            move || controller.load_more_floor()
+        });
+
+        controller.follow_player();
+        controller
+    }
+
+    /// 评论区跟随正在播放的歌曲切换
+    fn follow_player(&self) {
+        let backend = get_backend();
+        let mut events = backend.subscribe();
+        if let Some(song) = backend.status().song {
+            self.show(ResourceType::Song, song.id);
+        }
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            loop {
+                let song = match events.recv().await {
+                    Ok(PlayerEvent::TrackStarted { song, .. }) => song,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => match get_backend().status().song {
+                        Some(song) => song,
+                        None => continue,
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                // 排序方式要在界面线程读取
+                let controller = controller.clone();
+                let _ = controller
+                    .window
+                    .clone()
+                    .upgrade_in_event_loop(move |_| controller.show(ResourceType::Song, song.id));
+            }
+        });
+    }
+
+    /// 切换评论区对应的资源并加载第一页
+    pub fn show(&self, resource: ResourceType, id: u64) {
+        if let Ok(mut state) = self.state.lock() {
+            if state.target == Some((resource, id)) {
+                return;
+            }
+            state.target = Some((resource, id));
+        }
+        let sort = self
+            .window
+            .upgrade()
+            .map(|w| sort_code(w.get_comment_sort()))
+            .unwrap_or_default();
+        self.reload(CommentSort::from_code(sort));
+    }
+
+    fn reload(&self, sort: CommentSort) {
+        if let Ok(mut state) = self.state.lock() {
+            state.paging = None;
+            state.comments.clear();
+            state.floor = None;
+        }
+        self.sync_floor();
+        self.fetch(CommentPaging::new(sort));
+    }
+
+    fn load_more(&self) {
+        let paging = self
+            .state
+            .lock()
+            .ok()
+            .and_then(|state| state.paging.clone());
+        if let Some(paging) = paging {
+            self.fetch(paging);
+        }
+    }
+
+    fn fetch(&self, paging: CommentPaging) {
+        let Some((resource, id)) = self.target() else {
+            return;
+        };
+        self.set_loading(true);
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            let result = match paging.sort {
+                CommentSort::Hot => {
+                    comment::get_hot_comments(resource, id, paging.page_size, paging.offset()).await
+                }
+                _ => comment::get_comments(resource, id, &paging).await,
+            };
+            match result {
+                Ok(page) => {
+                    let total = page.total;
+                    let has_more = page.has_more;
+                    if let Ok(mut state) = controller.state.lock() {
+                        // 请求期间切换了资源，丢弃结果
+                        if state.target != Some((resource, id)) {
+                            return;
+                        }
+                        state.paging = has_more.then(|| paging.next(&page));
+                        state.comments.extend(page.comments);
+                    }
+                    controller.sync(Some(total), has_more);
+                }
+                Err(e) => {
+                    error!("Failed to load comments: {}", e);
+                    controller.set_loading(false);
+                }
+            }
+        });
+    }
+
+    /// 展开或收起评论的楼层回复
+    fn toggle_floor(&self, parent_id: SharedString) {
+        let Ok(parent_id) = parent_id.parse::<u64>() else {
+            return;
+        };
+        let expand = if let Ok(mut state) = self.state.lock() {
+            let expanded = state.floor.as_ref().map(|f| f.parent_id) == Some(parent_id);
+            state.floor = (!expanded).then(|| FloorState {
+                parent_id,
+                comments: Vec::new(),
+                cursor: None,
+                has_more: false,
+            });
+            !expanded
+        } else {
+            false
+        };
+        self.sync_floor();
+        if expand {
+            self.fetch_floor(parent_id, None);
+        }
+    }
+
+    fn load_more_floor(&self) {
+        let next = self.state.lock().ok().and_then(|state| {
+            let floor = state.floor.as_ref().filter(|f| f.has_more)?;
+            Some((floor.parent_id, floor.cursor.clone()))
+        });
+        if let Some((parent_id, cursor)) = next {
+            self.fetch_floor(parent_id, cursor);
+        }
+    }
+
+    fn fetch_floor(&self, parent_id: u64, cursor: Option<String>) {
+        let Some((resource, id)) = self.target() else {
+            return;
+        };
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            let result = comment::get_floor_comments(
+                resource,
+                id,
+                parent_id,
+                cursor.as_deref(),
+                FLOOR_PAGE_SIZE,
+            )
+            .await;
+            match result {
+                Ok(page) => {
+                    if let Ok(mut state) = controller.state.lock() {
+                        if state.target != Some((resource, id)) {
+                            return;
+                        }
+                        // 请求期间收起或切换了楼层，丢弃结果
+                        let Some(floor) = state.floor.as_mut().filter(|f| f.parent_id == parent_id)
+                        else {
+                            return;
+                        };
+                        floor.comments.extend(page.comments);
+                        floor.cursor = page.cursor;
+                        floor.has_more = page.has_more;
+                    }
+                    controller.sync_floor();
+                }
+                Err(e) => error!("Failed to load floor comments of {}: {}", parent_id, e),
+            }
+        });
+    }
+
+    fn post(&self, reply_to: Option<u64>, content: SharedString) {
+        let Some((resource, id)) = self.target() else {
+            return;
+        };
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            let result = match reply_to {
+                Some(comment_id) => {
+                    comment::reply_comment(resource, id, comment_id, &content).await
+                }
+                None => comment::post_comment(resource, id, &content).await,
+            };
+            match result {
+                Ok(posted) => {
+                    if let Ok(mut state) = controller.state.lock() {
+                        // 回复展开中的楼层时同时显示在楼层里
+                        if let Some(floor) = state
+                            .floor
+                            .as_mut()
+                            .filter(|f| Some(f.parent_id) == reply_to)
+                        {
+                            floor.comments.push(posted.clone());
+                        }
+                        state.comments.insert(0, posted);
+                    }
+                    controller.sync(None, controller.has_more());
+                    controller.sync_floor();
+                }
+                Err(e) => error!("Failed to post comment: {}", e),
+            }
+        });
+    }
+
+    fn like(&self, comment_id: SharedString, like: bool) {
+        let (Some((resource, id)), Ok(comment_id)) = (self.target(), comment_id.parse::<u64>())
+        else {
+            return;
+        };
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = comment::like_comment(resource, id, comment_id, like).await {
+                error!("Failed to like comment: {}", e);
+                return;
+            }
+            if let Ok(mut state) = controller.state.lock() {
+                let state = &mut *state;
+                let floor = state.floor.iter_mut().flat_map(|f| f.comments.iter_mut());
+                for c in state
+                    .comments
+                    .iter_mut()
+                    .chain(floor)
+                    .filter(|c| c.comment_id == comment_id && c.liked != like)
+                {
+                    c.liked = like;
+                    c.liked_count = if like {
+                        c.liked_count + 1
+                    } else {
+                        c.liked_count.saturating_sub(1)
+                    };
+                }
+            }
+            controller.sync(None, controller.has_more());
+            controller.sync_floor();
+        });
+    }
+
+    fn delete(&self, comment_id: SharedString) {
+        let (Some((resource, id)), Ok(comment_id)) = (self.target(), comment_id.parse::<u64>())
+        else {
+            return;
+        };
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = comment::delete_comment(resource, id, comment_id).await {
+                error!("Failed to delete comment: {}", e);
+                return;
+            }
+            if let Ok(mut state) = controller.state.lock() {
+                state.comments.retain(|c| c.comment_id != comment_id);
+                if let Some(floor) = state.floor.as_mut() {
+                    floor.comments.retain(|c| c.comment_id != comment_id);
+                }
+            }
+            controller.sync(None, controller.has_more());
+            controller.sync_floor();
+        });
+    }
+
+    fn target(&self) -> Option<(ResourceType, u64)> {
+        self.state.lock().ok().and_then(|state| state.target)
+    }
+
+    fn has_more(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.paging.is_some())
+            .unwrap_or(false)
+    }
+
+    fn set_loading(&self, loading: bool) {
+        let _ = self
+            .window
+            .upgrade_in_event_loop(move |w| w.set_comment_loading(loading));
+    }
+
+    /// 将展开的楼层回复同步到界面
+    fn sync_floor(&self) {
+        let Ok((parent, items, has_more)) = self.state.lock().map(|state| match &state.floor {
+            Some(floor) => (
+                floor.parent_id.to_string(),
+                floor.comments.iter().map(to_item).collect::<Vec<_>>(),
+                floor.has_more,
+            ),
+            None => (String::new(), Vec::new(), false),
+        }) else {
+            return;
+        };
+
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_comment_floor_parent(parent.into());
+            w.set_comment_floor(ModelRc::new(VecModel::from(items)));
+            w.set_comment_floor_has_more(has_more);
+        });
+    }
+
+    /// 将评论列表同步到界面
+    fn sync(&self, total: Option<u64>, has_more: bool) {
+        let Ok(items) = self
+            .state
+            .lock()
+            .map(|state| state.comments.iter().map(to_item).collect::<Vec<_>>())
+        else {
+            return;
+        };
+
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_comments(ModelRc::new(VecModel::from(items)));
+            if let Some(total) = total {
+                w.set_comment_total(total as i32);
+            }
+            w.set_comment_has_more(has_more);
+            w.set_comment_loading(false);
+        });
+    }
+}
+
+/// 界面下拉框的索引与接口排序方式之间的转换
+fn sort_code(index: i32) -> i32 {
+    match index {
+        1 => CommentSort::Hot.code() as i32,
+        2 => CommentSort::Time.code() as i32,
+        _ => CommentSort::Recommend.code() as i32,
+    }
+}
@@ -0,0 +1,43 @@
+use log::error;
+use slint::{ComponentHandle, Weak};
+use tokio::runtime::Handle;
+
+use crate::MainWindow;
+use crate::service::like;
+use crate::storage::like::get_like_store;
+
+fn refresh(window: &Weak<MainWindow>) {
+    let _ = window.upgrade_in_event_loop(|w| w.set_like_revision(w.get_like_revision() + 1));
+}
+
+/// 绑定喜欢按钮，并启动喜欢列表的后台同步
+pub fn bind(window: &MainWindow, runtime: Handle) {
+    window.on_is_liked(|id| id.parse().map(like::is_liked).unwrap_or(false));
+
+    window.on_toggle_like({
+        let window = window.as_weak();
+        let runtime = runtime.clone();
+        move |id, liked| {
+            let Ok(id) = id.parse::<u64>() else {
+                return;
+            };
+            // 先写入本地状态让按钮立即响应，请求失败时由服务层回滚
+            get_like_store().set_liked(id, liked);
+            refresh(&window);
+
+            let window = window.clone();
+            runtime.spawn(async move {
+                let result = like::like_song(id, liked).await;
+                refresh(&window);
+                if let Err(e) = result {
+                    error!("Failed to like song {}: {}", id, e);
+                }
+            });
+        }
+    });
+
+    like::spawn_sync_task(&runtime, {
+        let window = window.as_weak();
+        move || refresh(&window)
+    });
+}
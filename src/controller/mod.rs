@@ -0,0 +1,14 @@
+pub mod artist;
+pub mod cloud;
+pub mod comment;
+pub mod like;
+pub mod player;
+pub mod playlist;
+pub mod profile;
+pub mod radio;
+pub mod recommend;
+pub mod search;
+pub mod settings;
+pub mod similar;
+pub mod toplist;
+pub mod video;
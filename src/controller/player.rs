@@ -0,0 +1,134 @@
+use log::{error, warn};
+use slint::{ComponentHandle, Weak};
+use tokio::runtime::Handle;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::MainWindow;
+use crate::audio::engine::get_backend;
+use crate::controller::playlist::to_tracks;
+use crate::models::audio::{BackendState, PlayMode, PlaybackState, PlayerEvent, PlayerStatus};
+use crate::models::song::Song;
+
+/// 与队列页面播放模式下拉框的顺序一致
+const MODES: [PlayMode; 4] = [
+    PlayMode::Sequential,
+    PlayMode::RepeatAll,
+    PlayMode::RepeatOne,
+    PlayMode::Shuffle,
+];
+
+fn send(command: BackendState) {
+    if let Err(e) = get_backend().command_sender.send(command) {
+        error!("Failed to send audio command: {}", e);
+    }
+}
+
+fn set_state(window: &MainWindow, state: PlaybackState) {
+    window.set_playing(state == PlaybackState::Playing);
+    window.set_player_loading(state == PlaybackState::Loading);
+}
+
+fn set_song(window: &MainWindow, song: &Song) {
+    window.set_now_playing_title(song.name.as_str().into());
+    window.set_now_playing_artist(song.artist_names().into());
+    let artist_id = song.artists.first().map(|a| a.id.to_string());
+    window.set_now_playing_artist_id(artist_id.unwrap_or_default().into());
+    window.set_now_playing_album(song.album.name.as_str().into());
+    window.set_now_playing_album_id(song.album.id.to_string().into());
+    window.set_now_playing_id(song.id.to_string().into());
+    let mv = if song.mv > 0 {
+        song.mv.to_string()
+    } else {
+        String::new()
+    };
+    window.set_now_playing_mv(mv.into());
+}
+
+fn set_queue(window: &MainWindow, songs: &[Song], index: Option<usize>, mode: PlayMode) {
+    window.set_queue_tracks(to_tracks(songs));
+    window.set_queue_current(index.map_or(-1, |i| i as i32));
+    window.set_queue_mode(MODES.iter().position(|m| *m == mode).unwrap_or(0) as i32);
+}
+
+fn apply_status(window: &MainWindow, status: &PlayerStatus) {
+    set_state(window, status.state);
+    if let Some(song) = &status.song {
+        set_song(window, song);
+    }
+    window.set_position_ms(status.position.as_millis() as i32);
+    window.set_duration_ms(status.duration.unwrap_or_default().as_millis() as i32);
+    window.set_buffered(status.buffered_percent.unwrap_or(0.0) / 100.0);
+    set_queue(window, &status.queue, status.queue_index, status.mode);
+    window.set_output_name(status.output.as_str().into());
+}
+
+fn apply_event(window: &MainWindow, event: PlayerEvent) {
+    match event {
+        PlayerEvent::StateChanged(state) => set_state(window, state),
+        PlayerEvent::TrackStarted { song, .. } => {
+            set_song(window, &song);
+            window.set_player_error("".into());
+            window.set_position_ms(0);
+            window.set_buffered(0.0);
+        }
+        PlayerEvent::Position { position, duration } => {
+            window.set_position_ms(position.as_millis() as i32);
+            window.set_duration_ms(duration.unwrap_or_default().as_millis() as i32);
+        }
+        PlayerEvent::Buffering { percent } => {
+            window.set_buffered(percent.unwrap_or(0.0) / 100.0);
+        }
+        PlayerEvent::Error(message) => window.set_player_error(message.into()),
+        PlayerEvent::OutputChanged { device, fallback } => {
+            if fallback {
+                window.set_player_error(format!("输出设备不可用，已切换到 {}", device).into());
+            }
+            window.set_output_name(device.into());
+        }
+        PlayerEvent::QueueChanged { songs, index, mode } => {
+            set_queue(window, &songs, index, mode);
+        }
+        PlayerEvent::TrackEnded { .. } => {}
+    }
+}
+
+/// 把播放事件同步到界面，并绑定播放控制按钮
+pub fn bind(window: &MainWindow, runtime: Handle) {
+    window.on_toggle_play(|play| send(BackendState::Play(play)));
+    window.on_play_previous(|| send(BackendState::Previous));
+    window.on_play_next(|| send(BackendState::Next));
+    window.on_seek(|ms| send(BackendState::Seek(ms.max(0) as u64)));
+    window.on_queue_jump(|index| send(BackendState::Jump(index.max(0) as usize)));
+    window.on_queue_remove(|index| send(BackendState::Remove(index.max(0) as usize)));
+    window.on_queue_move(|from, to| {
+        send(BackendState::Move(from.max(0) as usize, to.max(0) as usize))
+    });
+    window.on_clear_queue(|| send(BackendState::ClearQueue));
+    window.on_set_queue_mode(|index| {
+        if let Some(mode) = MODES.get(index.max(0) as usize) {
+            send(BackendState::Mode(*mode));
+        }
+    });
+
+    let backend = get_backend();
+    // 先订阅再读取快照，避免两者之间的事件丢失
+    let mut events = backend.subscribe();
+    apply_status(window, &backend.status());
+
+    let window: Weak<MainWindow> = window.as_weak();
+    runtime.spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let _ = window.upgrade_in_event_loop(move |w| apply_event(&w, event));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Player view lagged behind, {} event(s) dropped", skipped);
+                    let status = get_backend().status();
+                    let _ = window.upgrade_in_event_loop(move |w| apply_status(&w, &status));
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
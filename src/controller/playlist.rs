@@ -0,0 +1,481 @@
+use log::error;
+use slint::{ComponentHandle, ModelRc, VecModel, Weak};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+use crate::audio::engine::get_backend;
+use crate::controller::profile::to_items;
+use crate::error::AppError;
+use crate::models::audio::{BackendState, PlayOrigin};
+use crate::models::playlist::{Playlist, PlaylistPrivacy};
+use crate::models::song::Song;
+use crate::service::auth::get_login_uid;
+use crate::service::playlist::{self, PlaylistDetail, PlaylistLoader};
+use crate::{MainWindow, PlaylistInfo, PlaylistItem, TrackItem};
+
+/// 用户歌单列表一次全部取回
+const PLAYLIST_LIMIT: u32 = 1000;
+
+fn send(command: BackendState) {
+    if let Err(e) = get_backend().command_sender.send(command) {
+        error!("Failed to send audio command: {}", e);
+    }
+}
+
+pub fn format_duration(millis: u64) -> String {
+    let seconds = millis / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+pub fn to_tracks(songs: &[Song]) -> ModelRc<TrackItem> {
+    let items: Vec<TrackItem> = songs
+        .iter()
+        .map(|s| TrackItem {
+            id: s.id.to_string().into(),
+            title: s.name.as_str().into(),
+            artist: s.artist_names().into(),
+            album: s.album.name.as_str().into(),
+            duration: format_duration(s.duration).into(),
+        })
+        .collect();
+    ModelRc::new(VecModel::from(items))
+}
+
+fn to_info(playlist: &Playlist, uid: Option<u64>) -> PlaylistInfo {
+    PlaylistInfo {
+        id: playlist.id.to_string().into(),
+        name: playlist.name.as_str().into(),
+        description: playlist.description.as_deref().unwrap_or_default().into(),
+        tags: playlist.tags.join(" ").into(),
+        creator: playlist
+            .creator
+            .as_ref()
+            .map(|c| c.nickname.as_str())
+            .unwrap_or_default()
+            .into(),
+        track_count: playlist.track_count as i32,
+        play_count: playlist.play_count as i32,
+        private: playlist.privacy() == PlaylistPrivacy::Private,
+        owned: uid == Some(playlist.user_id),
+        subscribed: playlist.subscribed.unwrap_or(false),
+    }
+}
+
+#[derive(Default)]
+struct LibraryState {
+    uid: Option<u64>,
+    playlists: Vec<Playlist>,
+    detail: Option<PlaylistDetail>,
+    /// 正在加载的歌单，打开其他歌单时取消
+    loader: Option<PlaylistLoader>,
+}
+
+/// 歌单页面：歌单列表、歌单详情与曲目管理
+#[derive(Clone)]
+pub struct PlaylistController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    state: Arc<Mutex<LibraryState>>,
+}
+
+impl PlaylistController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            state: Arc::new(Mutex::new(LibraryState::default())),
+        };
+
+        window.on_load_library({
+            let controller = controller.clone();
+            move || controller.load()
+        });
+        window.on_open_playlist({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.trim().parse() {
+                    controller.open(id);
+                }
+            }
+        });
+        window.on_create_playlist({
+            let controller = controller.clone();
+            move |name, private| controller.create(name.trim().to_string(), private)
+        });
+        window.on_remove_playlist({
+            let controller = controller.clone();
+            move |item| {
+                if let Ok(id) = item.id.parse() {
+                    controller.remove(id);
+                }
+            }
+        });
+        window.on_move_playlist({
+            let controller = controller.clone();
+            move |from, to| controller.move_playlist(from as usize, to as usize)
+        });
+        window.on_save_playlist({
+            let controller = controller.clone();
+            move |info| controller.save(info)
+        });
+        window.on_subscribe_playlist({
+            let controller = controller.clone();
+            move |id, subscribe| {
+                if let Ok(id) = id.parse() {
+                    controller.subscribe(id, subscribe);
+                }
+            }
+        });
+        window.on_cancel_playlist({
+            let controller = controller.clone();
+            move || {
+                if let Some(loader) = controller.lock().loader.take() {
+                    loader.cancel();
+                }
+            }
+        });
+        window.on_play_playlist({
+            let controller = controller.clone();
+            move |index| controller.play(index.max(0) as usize)
+        });
+        window.on_add_playlist_track({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.parse() {
+                    controller.add_track(id);
+                }
+            }
+        });
+        window.on_remove_playlist_track({
+            let controller = controller.clone();
+            move |index| controller.remove_track(index as usize)
+        });
+        window.on_move_playlist_track({
+            let controller = controller.clone();
+            move |from, to| controller.move_track(from as usize, to as usize)
+        });
+        window.on_queue_playlist_track({
+            let controller = controller.clone();
+            move |index, next| controller.queue_track(index as usize, next)
+        });
+
+        controller
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LibraryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn detail_id(&self) -> Option<u64> {
+        self.lock().detail.as_ref().map(|d| d.playlist.id)
+    }
+
+    /// 在后台执行操作，失败时显示错误
+    fn run<F>(&self, action: &'static str, task: F)
+    where
+        F: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = task.await {
+                error!("Failed to {}: {}", action, e);
+                controller.set_status(false, &format!("操作失败: {}", e));
+            }
+        });
+    }
+
+    /// 加载当前账号创建和收藏的歌单
+    pub fn load(&self) {
+        let controller = self.clone();
+        self.run("load playlists", async move {
+            let uid = get_login_uid()
+                .await?
+                .ok_or_else(|| AppError::Common("Not logged in".to_string()))?;
+            let playlists = playlist::get_user_playlists(uid, PLAYLIST_LIMIT, 0).await?;
+            let items = {
+                let mut state = controller.lock();
+                state.uid = Some(uid);
+                state.playlists = playlists;
+                to_items(&state.playlists)
+            };
+            controller.set_playlists(items);
+            Ok(())
+        });
+    }
+
+    fn set_playlists(&self, items: Vec<PlaylistItem>) {
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_library_playlists(ModelRc::new(VecModel::from(items)));
+        });
+    }
+
+    fn open(&self, id: u64) {
+        let loader = PlaylistLoader::new();
+        if let Some(previous) = self.lock().loader.replace(loader.clone()) {
+            previous.cancel();
+        }
+        self.set_status(true, "加载中...");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            // 进度回调跨越 await，需要 Sync
+            let window = Mutex::new(controller.window.clone());
+            let result = loader
+                .load(id, |loaded, total| {
+                    let Ok(window) = window.lock() else {
+                        return;
+                    };
+                    let status = format!("已加载 {}/{}", loaded, total);
+                    let _ = window.upgrade_in_event_loop(move |w| {
+                        w.set_playlist_status(status.into());
+                    });
+                })
+                .await;
+            if loader.is_cancelled() {
+                controller.set_status(false, "");
+                return;
+            }
+
+            match result {
+                Ok(detail) => {
+                    let (info, tracks) = {
+                        let mut state = controller.lock();
+                        state.loader = None;
+                        let info = to_info(&detail.playlist, state.uid);
+                        let tracks = detail.tracks.clone();
+                        state.detail = Some(detail);
+                        (info, tracks)
+                    };
+                    let _ = controller.window.upgrade_in_event_loop(move |w| {
+                        w.set_playlist_detail(info);
+                        w.set_playlist_tracks(to_tracks(&tracks));
+                        w.set_playlist_loading(false);
+                        w.set_playlist_status("".into());
+                    });
+                }
+                Err(e) => {
+                    error!("Failed to load playlist {}: {}", id, e);
+                    controller.lock().loader = None;
+                    controller.set_status(false, &format!("加载失败: {}", e));
+                }
+            }
+        });
+    }
+
+    /// 歌单内容有变化时刷新列表与当前歌单
+    fn refresh(&self) {
+        self.load();
+        if let Some(id) = self.detail_id() {
+            self.open(id);
+        }
+    }
+
+    fn create(&self, name: String, private: bool) {
+        let privacy = if private {
+            PlaylistPrivacy::Private
+        } else {
+            PlaylistPrivacy::Public
+        };
+        let controller = self.clone();
+        self.run("create playlist", async move {
+            let created = playlist::create_playlist(&name, privacy).await?;
+            controller.load();
+            controller.open(created.id);
+            Ok(())
+        });
+    }
+
+    fn remove(&self, id: u64) {
+        let owned = {
+            let state = self.lock();
+            state
+                .playlists
+                .iter()
+                .any(|p| p.id == id && state.uid == Some(p.user_id))
+        };
+        let controller = self.clone();
+        self.run("remove playlist", async move {
+            if owned {
+                playlist::delete_playlists(&[id]).await?;
+            } else {
+                playlist::subscribe_playlist(id, false).await?;
+            }
+            if controller.detail_id() == Some(id) {
+                controller.lock().detail = None;
+                let _ = controller.window.upgrade_in_event_loop(|w| {
+                    w.set_playlist_detail(PlaylistInfo::default());
+                    w.set_playlist_tracks(ModelRc::default());
+                });
+            }
+            controller.load();
+            Ok(())
+        });
+    }
+
+    fn move_playlist(&self, from: usize, to: usize) {
+        let (ids, items) = {
+            let mut state = self.lock();
+            if from >= state.playlists.len() || to >= state.playlists.len() {
+                return;
+            }
+            let moved = state.playlists.remove(from);
+            state.playlists.insert(to, moved);
+            let ids: Vec<u64> = state.playlists.iter().map(|p| p.id).collect();
+            (ids, to_items(&state.playlists))
+        };
+        self.set_playlists(items);
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = playlist::reorder_playlists(&ids).await {
+                error!("Failed to reorder playlists: {}", e);
+                controller.set_status(false, &format!("操作失败: {}", e));
+                controller.load();
+            }
+        });
+    }
+
+    fn save(&self, info: PlaylistInfo) {
+        let Some(current) = self.lock().detail.as_ref().map(|d| d.playlist.clone()) else {
+            return;
+        };
+        let name = info.name.trim().to_string();
+        let description = info.description.trim().to_string();
+        let tags: Vec<String> = info.tags.split_whitespace().map(String::from).collect();
+
+        let controller = self.clone();
+        self.run("update playlist", async move {
+            if !name.is_empty() && name != current.name {
+                playlist::rename_playlist(current.id, &name).await?;
+            }
+            if description != current.description.as_deref().unwrap_or_default() {
+                playlist::update_description(current.id, &description).await?;
+            }
+            if tags != current.tags {
+                let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+                playlist::update_tags(current.id, &tags).await?;
+            }
+            controller.refresh();
+            Ok(())
+        });
+    }
+
+    fn subscribe(&self, id: u64, subscribe: bool) {
+        let controller = self.clone();
+        self.run("subscribe playlist", async move {
+            playlist::subscribe_playlist(id, subscribe).await?;
+            controller.refresh();
+            Ok(())
+        });
+    }
+
+    fn queue_track(&self, index: usize, next: bool) {
+        let Some(song) = self
+            .lock()
+            .detail
+            .as_ref()
+            .and_then(|d| d.tracks.get(index).cloned())
+        else {
+            return;
+        };
+        let songs = vec![song];
+        send(if next {
+            BackendState::PlayNext(songs)
+        } else {
+            BackendState::Append(songs)
+        });
+    }
+
+    fn play(&self, index: usize) {
+        let Some((id, tracks)) = self
+            .lock()
+            .detail
+            .as_ref()
+            .map(|d| (d.playlist.id, d.tracks.clone()))
+        else {
+            return;
+        };
+        send(BackendState::Replace(
+            tracks,
+            PlayOrigin::Playlist(id),
+            index,
+        ));
+    }
+
+    fn add_track(&self, song_id: u64) {
+        let Some(id) = self.detail_id() else {
+            return;
+        };
+        let controller = self.clone();
+        self.run("add track", async move {
+            playlist::add_tracks(id, &[song_id]).await?;
+            controller.refresh();
+            Ok(())
+        });
+    }
+
+    fn remove_track(&self, index: usize) {
+        let (id, song_id, tracks) = {
+            let mut state = self.lock();
+            let Some(detail) = state.detail.as_mut() else {
+                return;
+            };
+            if index >= detail.tracks.len() {
+                return;
+            }
+            let song = detail.tracks.remove(index);
+            (detail.playlist.id, song.id, detail.tracks.clone())
+        };
+        self.set_tracks(tracks);
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = playlist::remove_tracks(id, &[song_id]).await {
+                error!("Failed to remove track {} from {}: {}", song_id, id, e);
+                controller.set_status(false, &format!("操作失败: {}", e));
+            }
+            // 歌曲数量变化，刷新列表
+            controller.refresh();
+        });
+    }
+
+    fn move_track(&self, from: usize, to: usize) {
+        let (id, tracks) = {
+            let mut state = self.lock();
+            let Some(detail) = state.detail.as_mut() else {
+                return;
+            };
+            if from >= detail.tracks.len() || to >= detail.tracks.len() {
+                return;
+            }
+            let song = detail.tracks.remove(from);
+            detail.tracks.insert(to, song);
+            (detail.playlist.id, detail.tracks.clone())
+        };
+        let ids: Vec<u64> = tracks.iter().map(|s| s.id).collect();
+        self.set_tracks(tracks);
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = playlist::reorder_tracks(id, &ids).await {
+                error!("Failed to reorder tracks of {}: {}", id, e);
+                controller.set_status(false, &format!("操作失败: {}", e));
+                controller.open(id);
+            }
+        });
+    }
+
+    fn set_tracks(&self, tracks: Vec<Song>) {
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_playlist_tracks(to_tracks(&tracks));
+        });
+    }
+
+    fn set_status(&self, loading: bool, status: &str) {
+        let status = status.to_string();
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_playlist_loading(loading);
+            w.set_playlist_status(status.into());
+        });
+    }
+}
@@ -0,0 +1,236 @@
+use log::error;
+use slint::{ComponentHandle, ModelRc, VecModel, Weak};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+use crate::error::AppError;
+use crate::models::playlist::Playlist;
+use crate::models::user::{Gender, ProfileUpdate, UserDetail, UserProfile};
+use crate::service::auth::get_login_uid;
+use crate::service::user;
+use crate::{MainWindow, PlaylistItem, ProfileInfo};
+
+/// 网易云的生日、发行时间等日期按东八区零点存储
+const TZ_OFFSET_MS: i64 = 8 * 3600 * 1000;
+const DAY_MS: i64 = 24 * 3600 * 1000;
+
+/// 毫秒时间戳转为 `YYYY-MM-DD`，未设置日期时为空
+pub fn format_date(millis: i64) -> String {
+    if millis <= 0 {
+        return String::new();
+    }
+    // 公历日期换算，参见 http://howardhinnant.github.io/date_algorithms.html
+    let z = (millis + TZ_OFFSET_MS).div_euclid(DAY_MS) + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn parse_birthday(text: &str) -> Option<i64> {
+    let mut parts = text.trim().splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    Some(days * DAY_MS - TZ_OFFSET_MS)
+}
+
+fn to_info(detail: &UserDetail, editable: bool) -> ProfileInfo {
+    let profile = &detail.profile;
+    ProfileInfo {
+        uid: profile.user_id.to_string().into(),
+        nickname: profile.nickname.as_str().into(),
+        avatar_url: profile.avatar_url.as_str().into(),
+        signature: profile.signature.as_deref().unwrap_or_default().into(),
+        gender: profile.gender().code() as i32,
+        birthday: format_date(profile.birthday).into(),
+        province: profile.province.to_string().into(),
+        city: profile.city.to_string().into(),
+        level: detail.level as i32,
+        listen_songs: detail.listen_songs as i32,
+        follows: profile.follows as i32,
+        followers: profile.followeds as i32,
+        editable,
+    }
+}
+
+pub fn to_items(playlists: &[Playlist]) -> Vec<PlaylistItem> {
+    playlists
+        .iter()
+        .map(|p| PlaylistItem {
+            id: p.id.to_string().into(),
+            name: p.name.as_str().into(),
+            cover_url: p.cover_img_url.as_str().into(),
+            track_count: p.track_count as i32,
+        })
+        .collect()
+}
+
+/// 将表单中修改过的字段转为更新请求
+fn to_update(current: &UserProfile, info: &ProfileInfo) -> Result<ProfileUpdate, AppError> {
+    let birthday = match info.birthday.trim() {
+        "" => None,
+        text => Some(
+            parse_birthday(text)
+                .ok_or_else(|| AppError::Format(format!("Invalid birthday: {}", text)))?,
+        ),
+    };
+    let region = |text: &str| {
+        text.trim()
+            .parse::<u64>()
+            .map_err(|_| AppError::Format(format!("Invalid region code: {}", text)))
+    };
+
+    Ok(ProfileUpdate {
+        nickname: Some(info.nickname.trim().to_string()).filter(|n| n != &current.nickname),
+        signature: Some(info.signature.to_string()),
+        gender: Some(Gender::from_code(info.gender as i64)),
+        birthday,
+        province: Some(region(&info.province)?),
+        city: Some(region(&info.city)?),
+    })
+}
+
+/// 用户资料页面
+#[derive(Clone)]
+pub struct ProfileController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    /// 当前展示的资料，仅在查看自己时保存用于编辑
+    current: Arc<Mutex<Option<UserProfile>>>,
+}
+
+impl ProfileController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            current: Arc::new(Mutex::new(None)),
+        };
+
+        window.on_load_profile({
+            let controller = controller.clone();
+            move |uid| controller.load(uid.trim().parse().ok())
+        });
+        window.on_save_profile({
+            let controller = controller.clone();
+            move |info| controller.save(info)
+        });
+        window.on_upload_avatar({
+            let controller = controller.clone();
+            move |path| controller.upload_avatar(PathBuf::from(path.as_str()))
+        });
+
+        controller
+    }
+
+    /// 加载用户资料，`uid` 为 `None` 时加载当前登录账号
+    pub fn load(&self, uid: Option<u64>) {
+        self.set_status(true, "");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = controller.clone().fetch(uid).await {
+                error!("Failed to load profile: {}", e);
+                controller.set_status(false, &format!("加载失败: {}", e));
+            }
+        });
+    }
+
+    async fn fetch(self, uid: Option<u64>) -> Result<(), AppError> {
+        let login_uid = get_login_uid().await?;
+        let uid = uid
+            .or(login_uid)
+            .ok_or_else(|| AppError::Common("Not logged in".to_string()))?;
+        let detail = user::get_user_detail(uid).await?;
+        let editable = login_uid == Some(uid);
+
+        if let Ok(mut current) = self.current.lock() {
+            *current = editable.then(|| detail.profile.clone());
+        }
+
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_profile(to_info(&detail, editable));
+            w.set_created_playlists(ModelRc::new(VecModel::from(to_items(
+                &detail.created_playlists,
+            ))));
+            w.set_subscribed_playlists(ModelRc::new(VecModel::from(to_items(
+                &detail.subscribed_playlists,
+            ))));
+            w.set_profile_loading(false);
+            w.set_profile_status("".into());
+        });
+        Ok(())
+    }
+
+    fn save(&self, info: ProfileInfo) {
+        let Some(current) = self.current.lock().ok().and_then(|c| c.clone()) else {
+            return;
+        };
+        let update = match to_update(&current, &info) {
+            Ok(update) => update,
+            Err(e) => {
+                self.set_status(false, &e.to_string());
+                return;
+            }
+        };
+        self.set_status(true, "");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            match user::update_profile(&current, update).await {
+                Ok(()) => controller.load(Some(current.user_id)),
+                Err(e) => {
+                    error!("Failed to update profile: {}", e);
+                    controller.set_status(false, &format!("保存失败: {}", e));
+                }
+            }
+        });
+    }
+
+    fn upload_avatar(&self, path: PathBuf) {
+        let Some(uid) = self
+            .current
+            .lock()
+            .ok()
+            .and_then(|c| c.as_ref().map(|p| p.user_id))
+        else {
+            return;
+        };
+        self.set_status(true, "");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            match user::upload_avatar(uid, &path).await {
+                Ok(()) => controller.load(Some(uid)),
+                Err(e) => {
+                    error!("Failed to upload avatar: {}", e);
+                    controller.set_status(false, &format!("上传头像失败: {}", e));
+                }
+            }
+        });
+    }
+
+    fn set_status(&self, loading: bool, status: &str) {
+        let status = status.to_string();
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_profile_loading(loading);
+            w.set_profile_status(status.into());
+        });
+    }
+}
@@ -0,0 +1,350 @@
+use log::error;
+use slint::{ComponentHandle, ModelRc, VecModel, Weak};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+use crate::audio::engine::get_backend;
+use crate::controller::artist::to_entries;
+use crate::controller::playlist::format_duration;
+use crate::controller::profile::format_date;
+use crate::error::AppError;
+use crate::models::audio::{BackendState, PlayOrigin, QueueSource};
+use crate::models::dj::{DjCategory, DjProgram, DjRadio, ProgramSort};
+use crate::service::dj;
+use crate::{EntryItem, MainWindow, RadioInfo, RadioItem};
+
+/// 订阅列表只展示第一页
+const SUBSCRIBED_LIMIT: u32 = 100;
+const CATEGORY_LIMIT: u32 = 50;
+const PROGRAM_PAGE: u32 = 30;
+
+fn send(command: BackendState) {
+    if let Err(e) = get_backend().command_sender.send(command) {
+        error!("Failed to send audio command: {}", e);
+    }
+}
+
+fn to_items(radios: &[DjRadio]) -> ModelRc<RadioItem> {
+    let items: Vec<RadioItem> = radios
+        .iter()
+        .map(|r| RadioItem {
+            id: r.id.to_string().into(),
+            name: r.name.as_str().into(),
+            dj: dj_name(r).into(),
+            category: r.category.as_str().into(),
+            program_count: r.program_count as i32,
+        })
+        .collect();
+    ModelRc::new(VecModel::from(items))
+}
+
+fn dj_name(radio: &DjRadio) -> &str {
+    radio
+        .dj
+        .as_ref()
+        .map(|dj| dj.nickname.as_str())
+        .unwrap_or_default()
+}
+
+fn to_radio_info(radio: &DjRadio) -> RadioInfo {
+    RadioInfo {
+        id: radio.id.to_string().into(),
+        name: radio.name.as_str().into(),
+        dj: dj_name(radio).into(),
+        category_id: radio.category_id.to_string().into(),
+        category: radio.category.as_str().into(),
+        counts: format!("{} 期  {} 人订阅", radio.program_count, radio.sub_count).into(),
+        description: radio.desc.as_str().into(),
+        subscribed: radio.sub_ed,
+    }
+}
+
+fn category_entry(category: &DjCategory) -> EntryItem {
+    EntryItem {
+        id: category.id.to_string().into(),
+        name: category.name.as_str().into(),
+        detail: "".into(),
+    }
+}
+
+fn program_entry(program: &DjProgram) -> EntryItem {
+    EntryItem {
+        id: program.id.to_string().into(),
+        name: format!("第{}期  {}", program.serial_num, program.name).into(),
+        detail: format!(
+            "{}  {}  收听 {}",
+            format_date(program.create_time),
+            format_duration(program.duration),
+            program.listener_count
+        )
+        .into(),
+    }
+}
+
+#[derive(Default)]
+struct RadioState {
+    categories: Vec<DjCategory>,
+    radio: Option<DjRadio>,
+    sort: ProgramSort,
+    programs: Vec<DjProgram>,
+}
+
+/// 电台页面
+#[derive(Clone)]
+pub struct RadioController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    state: Arc<Mutex<RadioState>>,
+}
+
+impl RadioController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            state: Arc::default(),
+        };
+
+        window.on_load_radios({
+            let controller = controller.clone();
+            move || controller.load()
+        });
+        window.on_open_radio_category({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.parse() {
+                    controller.open_category(id);
+                }
+            }
+        });
+        window.on_open_radio({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.trim().parse() {
+                    controller.open_radio(id);
+                }
+            }
+        });
+        window.on_subscribe_radio({
+            let controller = controller.clone();
+            move |id, subscribe| {
+                if let Ok(id) = id.trim().parse() {
+                    controller.subscribe(id, subscribe);
+                }
+            }
+        });
+        window.on_play_radio(|id, from_first| {
+            let Ok(radio_id) = id.trim().parse() else {
+                return;
+            };
+            let sort = if from_first {
+                ProgramSort::Oldest
+            } else {
+                ProgramSort::Newest
+            };
+            send(BackendState::Source(Some(QueueSource::DjRadio {
+                radio_id,
+                sort,
+            })));
+        });
+        window.on_sort_radio_programs({
+            let controller = controller.clone();
+            move |asc| {
+                let sort = if asc {
+                    ProgramSort::Oldest
+                } else {
+                    ProgramSort::Newest
+                };
+                controller.sort_programs(sort);
+            }
+        });
+        window.on_load_more_radio_programs({
+            let controller = controller.clone();
+            move || controller.load_more_programs()
+        });
+        window.on_play_radio_program({
+            let controller = controller.clone();
+            move |index| controller.play_program(index.max(0) as usize)
+        });
+        window.on_open_radio_program({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.trim().parse() {
+                    controller.open_program(id);
+                }
+            }
+        });
+
+        controller
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RadioState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 在后台执行操作，失败时显示错误
+    fn run<F>(&self, action: &'static str, task: F)
+    where
+        F: Future<Output = Result<(), AppError>> + Send + 'static,
+    {
+        self.set_status(true, "");
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            match task.await {
+                Ok(()) => controller.set_status(false, ""),
+                Err(e) => {
+                    error!("Failed to {}: {}", action, e);
+                    controller.set_status(false, &format!("操作失败: {}", e));
+                }
+            }
+        });
+    }
+
+    /// 加载已订阅的电台与电台分类
+    pub fn load(&self) {
+        let controller = self.clone();
+        self.run("load radios", async move {
+            let (radios, categories) = tokio::try_join!(
+                dj::get_subscribed_radios(SUBSCRIBED_LIMIT, 0),
+                dj::get_categories(),
+            )?;
+            controller.lock().categories = categories.clone();
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_radios(to_items(&radios));
+                w.set_radio_categories(to_entries(&categories, category_entry));
+            });
+            Ok(())
+        });
+    }
+
+    fn open_category(&self, id: u64) {
+        let name = self
+            .lock()
+            .categories
+            .iter()
+            .find(|c| c.id == id)
+            .map(|c| c.name.clone())
+            .unwrap_or_default();
+
+        let window = self.window.clone();
+        self.run("load category radios", async move {
+            let radios = dj::get_category_radios(id, CATEGORY_LIMIT, 0).await?;
+            let _ = window.upgrade_in_event_loop(move |w| {
+                w.set_radio_category_name(name.into());
+                w.set_category_radios(to_items(&radios));
+            });
+            Ok(())
+        });
+    }
+
+    pub fn open_radio(&self, id: u64) {
+        let sort = self.lock().sort;
+        let controller = self.clone();
+        self.run("load radio", async move {
+            let (radio, page) = tokio::try_join!(
+                dj::get_radio(id),
+                dj::get_programs(id, sort, PROGRAM_PAGE, 0),
+            )?;
+            let info = to_radio_info(&radio);
+            let programs = page.programs.clone();
+            {
+                let mut state = controller.lock();
+                state.radio = Some(radio);
+                state.programs = page.programs;
+            }
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_radio(info);
+                w.set_radio_programs(to_entries(&programs, program_entry));
+                w.set_radio_programs_has_more(page.has_more);
+                w.set_radio_programs_asc(sort.is_asc());
+            });
+            Ok(())
+        });
+    }
+
+    fn sort_programs(&self, sort: ProgramSort) {
+        let id = {
+            let mut state = self.lock();
+            state.sort = sort;
+            state.radio.as_ref().map(|r| r.id)
+        };
+        if let Some(id) = id {
+            self.open_radio(id);
+        }
+    }
+
+    fn load_more_programs(&self) {
+        let Some((id, sort, offset)) = ({
+            let state = self.lock();
+            state
+                .radio
+                .as_ref()
+                .map(|r| (r.id, state.sort, state.programs.len() as u32))
+        }) else {
+            return;
+        };
+
+        let controller = self.clone();
+        self.run("load radio programs", async move {
+            let page = dj::get_programs(id, sort, PROGRAM_PAGE, offset).await?;
+            let programs = {
+                let mut state = controller.lock();
+                state.programs.extend(page.programs);
+                state.programs.clone()
+            };
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_radio_programs(to_entries(&programs, program_entry));
+                w.set_radio_programs_has_more(page.has_more);
+            });
+            Ok(())
+        });
+    }
+
+    /// 播放详情页中已加载的节目
+    fn play_program(&self, index: usize) {
+        let Some((id, songs)) = ({
+            let state = self.lock();
+            state.radio.as_ref().map(|r| {
+                let songs = state.programs.iter().map(|p| p.main_song.clone()).collect();
+                (r.id, songs)
+            })
+        }) else {
+            return;
+        };
+        send(BackendState::Replace(songs, PlayOrigin::DjRadio(id), index));
+    }
+
+    /// 按节目ID播放单个节目
+    fn open_program(&self, id: u64) {
+        self.run("load radio program", async move {
+            let program = dj::get_program(id).await?;
+            let radio_id = program.radio.map(|r| r.id).unwrap_or_default();
+            send(BackendState::Replace(
+                vec![program.main_song],
+                PlayOrigin::DjRadio(radio_id),
+                0,
+            ));
+            Ok(())
+        });
+    }
+
+    fn subscribe(&self, id: u64, subscribe: bool) {
+        let controller = self.clone();
+        self.run("update radio subscription", async move {
+            dj::subscribe_radio(id, subscribe).await?;
+            controller.load();
+            if controller.lock().radio.as_ref().map(|r| r.id) == Some(id) {
+                controller.open_radio(id);
+            }
+            Ok(())
+        });
+    }
+
+    fn set_status(&self, loading: bool, status: &str) {
+        let status = status.to_string();
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_radio_loading(loading);
+            w.set_radio_status(status.into());
+        });
+    }
+}
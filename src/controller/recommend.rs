@@ -0,0 +1,83 @@
+use log::error;
+use tokio::runtime::Handle;
+
+use crate::MainWindow;
+use crate::audio::engine::get_backend;
+use crate::error::AppError;
+use crate::models::audio::{BackendState, PlayOrigin, QueueSource};
+use crate::service::auth::get_login_uid;
+use crate::service::{playlist, recommend};
+
+fn send(command: BackendState) {
+    if let Err(e) = get_backend().command_sender.send(command) {
+        error!("Failed to send audio command: {}", e);
+    }
+}
+
+/// 心动模式需要以"我喜欢的音乐"歌单为范围
+async fn liked_playlist_id() -> Result<u64, AppError> {
+    let uid = get_login_uid()
+        .await?
+        .ok_or_else(|| AppError::Common("Heartbeat mode requires login".to_string()))?;
+
+    playlist::get_user_playlists(uid, 1, 0)
+        .await?
+        .into_iter()
+        .find(|p| p.is_liked_playlist())
+        .map(|p| p.id)
+        .ok_or_else(|| AppError::Common("Liked playlist not found".to_string()))
+}
+
+pub fn bind(window: &MainWindow, runtime: Handle) {
+    window.on_play_daily({
+        let runtime = runtime.clone();
+        move || {
+            runtime.spawn(async {
+                match recommend::get_daily_songs().await {
+                    Ok(songs) => send(BackendState::Replace(songs, PlayOrigin::DailyRecommend, 0)),
+                    Err(e) => error!("Failed to load daily recommendations: {}", e),
+                }
+            });
+        }
+    });
+
+    window.on_play_fm(|| send(BackendState::Source(Some(QueueSource::PersonalFm))));
+
+    window.on_fm_trash({
+        let runtime = runtime.clone();
+        move |id| {
+            let Ok(id) = id.parse::<u64>() else {
+                return;
+            };
+            runtime.spawn(async move {
+                if let Err(e) = recommend::fm_trash(id).await {
+                    error!("Failed to trash FM track {}: {}", id, e);
+                }
+                send(BackendState::Next);
+            });
+        }
+    });
+
+    window.on_start_song_radio(|id| {
+        if let Ok(song_id) = id.parse::<u64>() {
+            send(BackendState::Source(Some(QueueSource::SongRadio {
+                song_id,
+            })));
+        }
+    });
+
+    window.on_start_intelligence(move |id| {
+        let Ok(song_id) = id.parse::<u64>() else {
+            return;
+        };
+        runtime.spawn(async move {
+            match liked_playlist_id().await {
+                Ok(playlist_id) => send(BackendState::Source(Some(QueueSource::Intelligence {
+                    song_id,
+                    playlist_id,
+                }))),
+                Err(e) => error!("Failed to start heartbeat mode: {}", e),
+            }
+        });
+    });
+}
@@ -0,0 +1,203 @@
+use log::{debug, error};
+use slint::{ComponentHandle, ModelRc, SharedString, VecModel, Weak};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::audio::engine::get_backend;
+use crate::models::audio::{BackendState, PlayOrigin};
+use crate::models::search::SearchSuggest;
+use crate::service::{search, song};
+use crate::storage::search_history::get_search_history;
+use crate::{HotSearchItem, MainWindow, SuggestItem};
+
+/// 停止输入后等待多久再请求搜索建议
+const SUGGEST_DEBOUNCE: Duration = Duration::from_millis(300);
+
+fn to_items(suggest: &SearchSuggest) -> Vec<SuggestItem> {
+    let mut items = Vec::new();
+    for kind in &suggest.order {
+        match kind.as_str() {
+            "songs" => items.extend(suggest.songs.iter().map(|s| SuggestItem {
+                kind: "单曲".into(),
+                id: s.id.to_string().into(),
+                title: s.name.as_str().into(),
+                subtitle: s.artist_names().into(),
+            })),
+            "artists" => items.extend(suggest.artists.iter().map(|a| SuggestItem {
+                kind: "歌手".into(),
+                id: a.id.to_string().into(),
+                title: a.name.as_str().into(),
+                subtitle: SharedString::default(),
+            })),
+            "albums" => items.extend(suggest.albums.iter().map(|a| SuggestItem {
+                kind: "专辑".into(),
+                id: a.id.to_string().into(),
+                title: a.name.as_str().into(),
+                subtitle: a.artist.name.as_str().into(),
+            })),
+            "playlists" => items.extend(suggest.playlists.iter().map(|p| SuggestItem {
+                kind: "歌单".into(),
+                id: p.id.to_string().into(),
+                title: p.name.as_str().into(),
+                subtitle: SharedString::default(),
+            })),
+            _ => {}
+        }
+    }
+    items
+}
+
+fn set_suggestions(window: &Weak<MainWindow>, items: Vec<SuggestItem>) {
+    let _ = window.upgrade_in_event_loop(move |w| {
+        w.set_search_suggestions(ModelRc::new(VecModel::from(items)));
+    });
+}
+
+fn sync_history(window: &Weak<MainWindow>) {
+    let history: Vec<SharedString> = get_search_history()
+        .list()
+        .into_iter()
+        .map(SharedString::from)
+        .collect();
+    let _ = window.upgrade_in_event_loop(move |w| {
+        w.set_search_history(ModelRc::new(VecModel::from(history)));
+    });
+}
+
+/// 搜索框，输入时请求搜索建议
+#[derive(Clone)]
+pub struct SearchController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    /// 尚未返回的建议请求，新的输入会取消它
+    pending: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl SearchController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            pending: Arc::new(Mutex::new(None)),
+        };
+
+        window.on_search_edited({
+            let controller = controller.clone();
+            move |text| controller.suggest(text.trim().to_string())
+        });
+        window.on_search_submit({
+            let controller = controller.clone();
+            move |keyword| controller.submit(&keyword)
+        });
+        window.on_search_select({
+            let controller = controller.clone();
+            move |item| controller.select(item)
+        });
+        window.on_remove_search_history({
+            let window = window.as_weak();
+            move |keyword| {
+                get_search_history().remove(&keyword);
+                sync_history(&window);
+            }
+        });
+        window.on_clear_search_history({
+            let window = window.as_weak();
+            move || {
+                get_search_history().clear();
+                sync_history(&window);
+            }
+        });
+
+        sync_history(&controller.window);
+        controller.load_hot();
+        controller
+    }
+
+    fn load_hot(&self) {
+        let window = self.window.clone();
+        self.runtime.spawn(async move {
+            match search::get_default_keyword().await {
+                Ok(keyword) => {
+                    let _ = window.upgrade_in_event_loop(move |w| {
+                        w.set_default_keyword(keyword.show_keyword.into())
+                    });
+                }
+                Err(e) => error!("Failed to load default keyword: {}", e),
+            }
+
+            match search::get_hot_searches().await {
+                Ok(hot) => {
+                    let items: Vec<HotSearchItem> = hot
+                        .into_iter()
+                        .map(|h| HotSearchItem {
+                            word: h.search_word.into(),
+                            content: h.content.into(),
+                            score: h.score as i32,
+                        })
+                        .collect();
+                    let _ = window.upgrade_in_event_loop(move |w| {
+                        w.set_hot_searches(ModelRc::new(VecModel::from(items)));
+                    });
+                }
+                Err(e) => error!("Failed to load hot searches: {}", e),
+            }
+        });
+    }
+
+    fn cancel_pending(&self) {
+        if let Some(handle) = self.pending.lock().ok().and_then(|mut p| p.take()) {
+            handle.abort();
+        }
+    }
+
+    /// 防抖后请求搜索建议，之前未完成的请求直接丢弃
+    fn suggest(&self, keyword: String) {
+        self.cancel_pending();
+        if keyword.is_empty() {
+            set_suggestions(&self.window, Vec::new());
+            return;
+        }
+
+        let window = self.window.clone();
+        let handle = self.runtime.spawn(async move {
+            tokio::time::sleep(SUGGEST_DEBOUNCE).await;
+            match search::get_suggestions(&keyword).await {
+                Ok(suggest) => set_suggestions(&window, to_items(&suggest)),
+                Err(e) => debug!("Failed to load suggestions for {}: {}", keyword, e),
+            }
+        });
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = Some(handle);
+        }
+    }
+
+    fn submit(&self, keyword: &str) {
+        self.cancel_pending();
+        get_search_history().add(keyword);
+        sync_history(&self.window);
+    }
+
+    fn select(&self, item: SuggestItem) {
+        self.submit(&item.title);
+        if item.kind != "单曲" {
+            return;
+        }
+        let Ok(id) = item.id.parse::<u64>() else {
+            return;
+        };
+
+        self.runtime.spawn(async move {
+            match song::get_song_details(&[id]).await {
+                Ok(songs) => {
+                    let command = BackendState::Replace(songs, PlayOrigin::Search, 0);
+                    if let Err(e) = get_backend().command_sender.send(command) {
+                        error!("Failed to send audio command: {}", e);
+                    }
+                }
+                Err(e) => error!("Failed to load song {}: {}", id, e),
+            }
+        });
+    }
+}
@@ -0,0 +1,280 @@
+use log::error;
+use slint::{ComponentHandle, ModelRc, SharedString, VecModel, Weak};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+use crate::MainWindow;
+use crate::audio::backend::{crossfade_config, dsp_settings};
+use crate::audio::engine::get_backend;
+use crate::audio::output;
+use crate::models::audio::{BackendState, CrossfadeConfig, FadeCurve, OutputDevice, OutputKind};
+use crate::models::dsp::{DspSettings, EqPreset};
+use crate::storage::eq_preset::get_eq_preset_store;
+
+/// 与设置页面曲线下拉框的顺序一致
+const CURVES: [FadeCurve; 3] = [FadeCurve::Linear, FadeCurve::EqualPower, FadeCurve::SCurve];
+
+fn send(command: BackendState) {
+    if let Err(e) = get_backend().command_sender.send(command) {
+        error!("Failed to send audio command: {}", e);
+    }
+}
+
+fn label(device: &OutputDevice) -> SharedString {
+    if device.is_default {
+        format!("{}（默认）", device.name).into()
+    } else {
+        device.name.as_str().into()
+    }
+}
+
+/// 汇总设备支持的声道数、采样率与采样格式
+fn describe(device: &OutputDevice) -> String {
+    let configs = &device.configs;
+    let (Some(channels), Some(min), Some(max)) = (
+        configs.iter().map(|c| c.channels).max(),
+        configs.iter().map(|c| c.min_sample_rate).min(),
+        configs.iter().map(|c| c.max_sample_rate).max(),
+    ) else {
+        return String::new();
+    };
+    let mut formats: Vec<&str> = configs.iter().map(|c| c.sample_format.as_str()).collect();
+    formats.sort_unstable();
+    formats.dedup();
+    format!(
+        "最多 {} 声道，{}–{} Hz，{}",
+        channels,
+        min,
+        max,
+        formats.join(" / ")
+    )
+}
+
+/// 内置预设在前，之后为用户保存的预设
+fn eq_presets() -> Vec<EqPreset> {
+    let mut presets = EqPreset::builtin();
+    presets.extend(get_eq_preset_store().list());
+    presets
+}
+
+fn show_dsp(window: &MainWindow, dsp: &DspSettings) {
+    let gains: Vec<f32> = dsp.bands.iter().map(|band| band.gain_db).collect();
+    window.set_eq_enabled(dsp.eq_enabled);
+    // 换成新的模型，拖动过的滑块也会重新绑定
+    window.set_eq_gains(ModelRc::new(VecModel::from(gains)));
+    window.set_preamp_db(dsp.preamp_db);
+    window.set_balance(dsp.balance);
+    window.set_mono(dsp.mono);
+}
+
+fn show_presets(window: &MainWindow, selected: Option<usize>) {
+    let names: Vec<SharedString> = eq_presets()
+        .iter()
+        .map(|preset| preset.name.as_str().into())
+        .collect();
+    window.set_eq_presets(ModelRc::new(VecModel::from(names)));
+    window.set_eq_builtin_count(EqPreset::builtin().len() as i32);
+    window.set_eq_preset_index(selected.map_or(-1, |i| i as i32));
+}
+
+/// 设置页面
+#[derive(Clone)]
+pub struct SettingsController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    /// 下拉框第一项为系统默认设备，之后依次对应这里的设备
+    devices: Arc<Mutex<Vec<OutputDevice>>>,
+    /// 当前的音效设置，每次修改后整体发给播放线程
+    dsp: Arc<Mutex<DspSettings>>,
+}
+
+impl SettingsController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            devices: Arc::default(),
+            dsp: Arc::new(Mutex::new(dsp_settings())),
+        };
+
+        let crossfade = crossfade_config();
+        window.set_crossfade_seconds(crossfade.duration().as_secs_f32());
+        window.set_crossfade_curve(
+            CURVES
+                .iter()
+                .position(|c| *c == crossfade.curve)
+                .unwrap_or(0) as i32,
+        );
+        window.on_set_crossfade(|seconds, curve| {
+            // 以半秒为单位
+            let duration_ms = ((seconds.max(0.0) * 2.0).round() * 500.0) as u64;
+            let curve = CURVES
+                .get(curve.max(0) as usize)
+                .copied()
+                .unwrap_or_default();
+            send(BackendState::Crossfade(CrossfadeConfig {
+                duration_ms: duration_ms.min(CrossfadeConfig::MAX_DURATION_MS),
+                curve,
+            }));
+        });
+
+        window.on_refresh_outputs({
+            let controller = controller.clone();
+            move || controller.refresh_outputs()
+        });
+        window.on_select_output({
+            let controller = controller.clone();
+            move |index| controller.select_output(index)
+        });
+        controller.refresh_outputs();
+
+        if let Ok(dsp) = controller.dsp.lock() {
+            show_dsp(window, &dsp);
+        }
+        show_presets(window, None);
+        window.on_set_dsp({
+            let controller = controller.clone();
+            move || {
+                let Some(w) = controller.window.upgrade() else {
+                    return;
+                };
+                controller.update_dsp(|dsp| {
+                    dsp.eq_enabled = w.get_eq_enabled();
+                    dsp.preamp_db = w.get_preamp_db().round();
+                    dsp.balance = (w.get_balance() * 100.0).round() / 100.0;
+                    dsp.mono = w.get_mono();
+                });
+            }
+        });
+        window.on_set_eq_gain({
+            let controller = controller.clone();
+            move |index, gain| {
+                controller.update_dsp(|dsp| {
+                    if let Some(band) = dsp.bands.get_mut(index.max(0) as usize) {
+                        band.gain_db = gain.round();
+                    }
+                });
+                if let Some(w) = controller.window.upgrade() {
+                    w.set_eq_preset_index(-1);
+                }
+            }
+        });
+        window.on_select_eq_preset({
+            let controller = controller.clone();
+            move |index| {
+                if let Some(preset) = eq_presets().get(index.max(0) as usize) {
+                    controller.update_dsp(|dsp| dsp.apply_preset(preset));
+                }
+            }
+        });
+        window.on_save_eq_preset({
+            let controller = controller.clone();
+            move |name| controller.save_preset(name.trim())
+        });
+        window.on_remove_eq_preset({
+            let controller = controller.clone();
+            move |index| {
+                let index = index.max(0) as usize;
+                // 内置预设不能删除
+                if index < EqPreset::builtin().len() {
+                    return;
+                }
+                let Some(preset) = eq_presets().into_iter().nth(index) else {
+                    return;
+                };
+                get_eq_preset_store().remove(&preset.name);
+                if let Some(w) = controller.window.upgrade() {
+                    show_presets(&w, None);
+                }
+            }
+        });
+
+        controller
+    }
+
+    /// 修改音效设置并立即发给播放线程
+    fn update_dsp(&self, change: impl FnOnce(&mut DspSettings)) {
+        let Ok(mut dsp) = self.dsp.lock() else {
+            return;
+        };
+        change(&mut dsp);
+        send(BackendState::Dsp(dsp.clone()));
+        if let Some(w) = self.window.upgrade() {
+            show_dsp(&w, &dsp);
+        }
+    }
+
+    /// 把当前的均衡器频段保存为预设，同名预设会被覆盖
+    fn save_preset(&self, name: &str) {
+        if name.is_empty() {
+            return;
+        }
+        let Ok(dsp) = self.dsp.lock() else {
+            return;
+        };
+        get_eq_preset_store().save(&EqPreset {
+            name: name.to_string(),
+            bands: dsp.bands.clone(),
+        });
+        drop(dsp);
+
+        let selected = eq_presets().iter().rposition(|preset| preset.name == name);
+        if let Some(w) = self.window.upgrade() {
+            show_presets(&w, selected);
+        }
+    }
+
+    /// 重新列出输出设备，选中正在使用的设备
+    fn refresh_outputs(&self) {
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            // 枚举设备要访问声卡驱动，可能阻塞较长时间
+            let devices = match tokio::task::spawn_blocking(output::devices).await {
+                Ok(devices) => devices,
+                Err(e) => {
+                    error!("Failed to list output devices: {}", e);
+                    return;
+                }
+            };
+            let current = get_backend().status().output;
+            let selected = devices.iter().position(|d| d.name == current);
+            let names: Vec<SharedString> = std::iter::once("系统默认".into())
+                .chain(devices.iter().map(label))
+                .collect();
+            let formats = selected.map(|i| describe(&devices[i])).unwrap_or_default();
+            if let Ok(mut stored) = controller.devices.lock() {
+                *stored = devices;
+            }
+
+            let _ = controller.window.upgrade_in_event_loop(move |w| {
+                w.set_output_devices(ModelRc::new(VecModel::from(names)));
+                w.set_output_index(selected.map_or(-1, |i| i as i32 + 1));
+                w.set_output_formats(formats.into());
+            });
+        });
+    }
+
+    fn select_output(&self, index: i32) {
+        let Ok(devices) = self.devices.lock() else {
+            return;
+        };
+        let (kind, device) = if index <= 0 {
+            (
+                OutputKind::Device(None),
+                devices.iter().find(|d| d.is_default),
+            )
+        } else {
+            let Some(device) = devices.get(index as usize - 1) else {
+                return;
+            };
+            (OutputKind::Device(Some(device.name.clone())), Some(device))
+        };
+        let formats = device.map(describe).unwrap_or_default();
+        drop(devices);
+
+        send(BackendState::Output(kind));
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_output_formats(formats.into());
+        });
+    }
+}
@@ -0,0 +1,88 @@
+use log::error;
+use slint::ComponentHandle;
+use tokio::runtime::Handle;
+
+use crate::audio::engine::get_backend;
+use crate::controller::artist::{artist_entry, to_entries};
+use crate::models::playlist::Playlist;
+use crate::models::user::UserProfile;
+use crate::service::similar;
+use crate::{EntryItem, MainWindow};
+
+const PLAYLIST_LIMIT: u32 = 20;
+const LISTENER_LIMIT: u32 = 30;
+
+fn playlist_entry(playlist: &Playlist) -> EntryItem {
+    let creator = playlist
+        .creator
+        .as_ref()
+        .map(|c| c.nickname.as_str())
+        .unwrap_or_default();
+    EntryItem {
+        id: playlist.id.to_string().into(),
+        name: playlist.name.as_str().into(),
+        detail: format!("{} 首  {}", playlist.track_count, creator).into(),
+    }
+}
+
+fn listener_entry(user: &UserProfile) -> EntryItem {
+    EntryItem {
+        id: user.user_id.to_string().into(),
+        name: user.nickname.as_str().into(),
+        detail: user.signature.as_deref().unwrap_or_default().into(),
+    }
+}
+
+/// 正在播放页面的相似推荐，按需为当前歌曲加载
+pub fn bind(window: &MainWindow, runtime: Handle) {
+    window.on_load_similar({
+        let window = window.as_weak();
+        move || {
+            let Some(song) = get_backend().status().song else {
+                return;
+            };
+            if let Some(w) = window.upgrade() {
+                w.set_similar_loading(true);
+                w.set_similar_status("".into());
+            }
+
+            let window = window.clone();
+            runtime.spawn(async move {
+                let (playlists, artists, listeners) = tokio::join!(
+                    similar::get_similar_playlists(song.id, PLAYLIST_LIMIT, 0),
+                    similar::get_similar_artists(&song),
+                    similar::get_listeners(song.id, LISTENER_LIMIT, 0),
+                );
+                // 相似歌手与在听用户需要登录，失败时照常显示加载到的部分
+                let errors: Vec<String> = [
+                    playlists.as_ref().err(),
+                    artists.as_ref().err(),
+                    listeners.as_ref().err(),
+                ]
+                .into_iter()
+                .flatten()
+                .map(|e| e.to_string())
+                .collect();
+                for e in &errors {
+                    error!("Failed to load similar content of {}: {}", song.id, e);
+                }
+                let status = errors
+                    .first()
+                    .map(|e| format!("部分推荐加载失败: {}", e))
+                    .unwrap_or_default();
+
+                let playlists = playlists.unwrap_or_default();
+                let artists = artists.unwrap_or_default();
+                let listeners = listeners.unwrap_or_default();
+                let _ = window.upgrade_in_event_loop(move |w| {
+                    w.set_similar_title(song.name.as_str().into());
+                    w.set_similar_playlists(to_entries(&playlists, playlist_entry));
+                    w.set_similar_song_artists(to_entries(&artists, artist_entry));
+                    w.set_similar_listeners(to_entries(&listeners, listener_entry));
+                    w.set_similar_loading(false);
+                    w.set_similar_status(status.into());
+                });
+            });
+        }
+    });
+}
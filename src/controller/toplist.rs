@@ -0,0 +1,250 @@
+use log::error;
+use slint::{ComponentHandle, ModelRc, VecModel, Weak};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+use crate::audio::engine::get_backend;
+use crate::controller::playlist::to_tracks;
+use crate::models::audio::{BackendState, PlayOrigin};
+use crate::models::song::Song;
+use crate::models::toplist::{ChartDetail, ChartDiff, Toplist};
+use crate::service::playlist::PlaylistLoader;
+use crate::service::toplist;
+use crate::{ChartInfo, ChartItem, MainWindow};
+
+fn to_items(toplists: &[Toplist]) -> ModelRc<ChartItem> {
+    let items: Vec<ChartItem> = toplists
+        .iter()
+        .map(|t| ChartItem {
+            id: t.id.to_string().into(),
+            name: t.name.as_str().into(),
+            cover_url: t.cover_img_url.as_str().into(),
+            frequency: t.update_frequency.as_str().into(),
+            updated: toplist::has_update(t),
+        })
+        .collect();
+    ModelRc::new(VecModel::from(items))
+}
+
+fn describe(diff: &ChartDiff) -> String {
+    if diff.first_view {
+        "首次查看".to_string()
+    } else if diff.new_entries.is_empty() && diff.dropped.is_empty() {
+        "与上次查看相比没有变化".to_string()
+    } else {
+        format!(
+            "新上榜 {} 首，跌出 {} 首",
+            diff.new_entries.len(),
+            diff.dropped.len()
+        )
+    }
+}
+
+/// 新上榜的歌曲，保持榜单内顺序
+fn new_songs(chart: &ChartDetail) -> Vec<Song> {
+    let new: HashSet<u64> = chart.diff.new_entries.iter().copied().collect();
+    chart
+        .tracks
+        .iter()
+        .filter(|s| new.contains(&s.id))
+        .cloned()
+        .collect()
+}
+
+#[derive(Default)]
+struct ToplistState {
+    official: Vec<Toplist>,
+    global: Vec<Toplist>,
+    chart: Option<ChartDetail>,
+    /// 正在加载的榜单，打开其他榜单时取消
+    loader: Option<PlaylistLoader>,
+}
+
+/// 排行榜页面
+#[derive(Clone)]
+pub struct ToplistController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+    state: Arc<Mutex<ToplistState>>,
+}
+
+impl ToplistController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+            state: Arc::default(),
+        };
+
+        window.on_load_toplists({
+            let controller = controller.clone();
+            move || controller.load()
+        });
+        window.on_open_chart({
+            let controller = controller.clone();
+            move |id| {
+                if let Ok(id) = id.parse() {
+                    controller.open(id);
+                }
+            }
+        });
+        window.on_cancel_chart({
+            let controller = controller.clone();
+            move || {
+                if let Some(loader) = controller.lock().loader.take() {
+                    loader.cancel();
+                }
+            }
+        });
+        window.on_play_chart({
+            let controller = controller.clone();
+            move |only_new, index| controller.play(only_new, index.max(0) as usize)
+        });
+
+        controller
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ToplistState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 加载全部官方榜与全球榜
+    pub fn load(&self) {
+        self.set_status(true, "");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            match toplist::get_toplists().await {
+                Ok(toplists) => {
+                    {
+                        let mut state = controller.lock();
+                        state.official = toplists.official;
+                        state.global = toplists.global;
+                    }
+                    controller.show_toplists();
+                    controller.set_status(false, "");
+                }
+                Err(e) => {
+                    error!("Failed to load toplists: {}", e);
+                    controller.set_status(false, &format!("加载失败: {}", e));
+                }
+            }
+        });
+    }
+
+    /// 刷新列表中的更新标记
+    fn show_toplists(&self) {
+        let (official, global) = {
+            let state = self.lock();
+            (state.official.clone(), state.global.clone())
+        };
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_official_charts(to_items(&official));
+            w.set_global_charts(to_items(&global));
+        });
+    }
+
+    fn open(&self, id: u64) {
+        let loader = PlaylistLoader::new();
+        let toplist = {
+            let mut state = self.lock();
+            let Some(toplist) = state
+                .official
+                .iter()
+                .chain(&state.global)
+                .find(|t| t.id == id)
+                .cloned()
+            else {
+                return;
+            };
+            if let Some(previous) = state.loader.replace(loader.clone()) {
+                previous.cancel();
+            }
+            toplist
+        };
+        self.set_status(true, "加载中...");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            // 进度回调跨越 await，需要 Sync
+            let window = Mutex::new(controller.window.clone());
+            let result = toplist::load_chart(&loader, toplist, |loaded, total| {
+                let Ok(window) = window.lock() else {
+                    return;
+                };
+                let status = format!("已加载 {}/{}", loaded, total);
+                let _ = window.upgrade_in_event_loop(move |w| {
+                    w.set_chart_status(status.into());
+                });
+            })
+            .await;
+            if loader.is_cancelled() {
+                controller.set_status(false, "");
+                return;
+            }
+
+            match result {
+                Ok(chart) => {
+                    let info = ChartInfo {
+                        id: chart.toplist.id.to_string().into(),
+                        name: chart.toplist.name.as_str().into(),
+                        description: chart
+                            .toplist
+                            .description
+                            .as_deref()
+                            .unwrap_or_default()
+                            .into(),
+                        frequency: chart.toplist.update_frequency.as_str().into(),
+                        changes: describe(&chart.diff).into(),
+                    };
+                    let tracks = chart.tracks.clone();
+                    let new_tracks = new_songs(&chart);
+                    {
+                        let mut state = controller.lock();
+                        state.loader = None;
+                        state.chart = Some(chart);
+                    }
+                    let _ = controller.window.upgrade_in_event_loop(move |w| {
+                        w.set_chart(info);
+                        w.set_chart_tracks(to_tracks(&tracks));
+                        w.set_chart_new_tracks(to_tracks(&new_tracks));
+                    });
+                    // 查看后不再标记为有更新
+                    controller.show_toplists();
+                    controller.set_status(false, "");
+                }
+                Err(e) => {
+                    error!("Failed to load chart {}: {}", id, e);
+                    controller.lock().loader = None;
+                    controller.set_status(false, &format!("加载失败: {}", e));
+                }
+            }
+        });
+    }
+
+    fn play(&self, only_new: bool, index: usize) {
+        let Some((id, songs)) = self.lock().chart.as_ref().map(|chart| {
+            let songs = if only_new {
+                new_songs(chart)
+            } else {
+                chart.tracks.clone()
+            };
+            (chart.toplist.id, songs)
+        }) else {
+            return;
+        };
+        let command = BackendState::Replace(songs, PlayOrigin::Toplist(id), index);
+        if let Err(e) = get_backend().command_sender.send(command) {
+            error!("Failed to send audio command: {}", e);
+        }
+    }
+
+    fn set_status(&self, loading: bool, status: &str) {
+        let status = status.to_string();
+        let _ = self.window.upgrade_in_event_loop(move |w| {
+            w.set_chart_loading(loading);
+            w.set_chart_status(status.into());
+        });
+    }
+}
@@ -0,0 +1,145 @@
+use log::error;
+use slint::{ComponentHandle, Weak};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::runtime::Handle;
+
+use crate::MainWindow;
+use crate::error::AppError;
+use crate::models::video::{Resolution, VideoPlayerConfig, VideoStream};
+use crate::service::video;
+use crate::video::player;
+
+/// 纯数字为 MV ID，否则按视频 ID 处理
+async fn resolve(id: &str, max: Resolution) -> Result<VideoStream, AppError> {
+    match id.parse::<u64>() {
+        Ok(mv_id) => {
+            let mv = video::get_mv_detail(mv_id).await?;
+            let available: Vec<u32> = mv.brs.iter().map(|b| b.br).collect();
+            video::get_mv_stream(&mv, Resolution::best(&available, max)).await
+        }
+        Err(_) => {
+            let detail = video::get_video_detail(id).await?;
+            let available: Vec<u32> = detail.resolutions.iter().map(|r| r.resolution).collect();
+            video::get_video_stream(&detail, Resolution::best(&available, max)).await
+        }
+    }
+}
+
+fn resolution_at(index: i32) -> Resolution {
+    Resolution::ALL
+        .get(index.max(0) as usize)
+        .copied()
+        .unwrap_or(Resolution::P1080)
+}
+
+/// MV 和视频的播放、下载
+#[derive(Clone)]
+pub struct VideoController {
+    window: Weak<MainWindow>,
+    runtime: Handle,
+}
+
+impl VideoController {
+    pub fn new(window: &MainWindow, runtime: Handle) -> Self {
+        let controller = Self {
+            window: window.as_weak(),
+            runtime,
+        };
+
+        let config = player::get_player_config();
+        window.set_video_player_program(config.program.into());
+        window.set_video_player_args(config.args.join(" ").into());
+
+        window.on_play_mv({
+            let controller = controller.clone();
+            move |id| controller.play(id.to_string(), Resolution::P1080)
+        });
+        window.on_play_video({
+            let controller = controller.clone();
+            move |id, index| controller.play(id.trim().to_string(), resolution_at(index))
+        });
+        window.on_download_video({
+            let controller = controller.clone();
+            move |id, index, path| {
+                controller.download(
+                    id.trim().to_string(),
+                    resolution_at(index),
+                    PathBuf::from(path.as_str()),
+                )
+            }
+        });
+        window.on_save_video_player(|program, args| {
+            player::set_player_config(&VideoPlayerConfig {
+                program: program.trim().to_string(),
+                args: args.split_whitespace().map(|s| s.to_string()).collect(),
+            });
+        });
+
+        controller
+    }
+
+    fn play(&self, id: String, max: Resolution) {
+        self.set_status("正在获取播放地址...");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            let result = match resolve(&id, max).await {
+                Ok(stream) => player::play(&stream),
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => controller.set_status(""),
+                Err(e) => {
+                    error!("Failed to play video {}: {}", id, e);
+                    controller.set_status(&format!("播放失败: {}", e));
+                }
+            }
+        });
+    }
+
+    fn download(&self, id: String, max: Resolution, path: PathBuf) {
+        self.set_status("正在获取下载地址...");
+
+        let controller = self.clone();
+        self.runtime.spawn(async move {
+            // 进度回调跨越 await，需要 Sync
+            let window = Mutex::new(controller.window.clone());
+            let last_percent = AtomicU64::new(u64::MAX);
+            let result = match resolve(&id, max).await {
+                Ok(stream) => {
+                    video::download(&stream, &path, |done, total| {
+                        let percent = (done * 100).checked_div(total).unwrap_or(0);
+                        if last_percent.swap(percent, Ordering::Relaxed) == percent {
+                            return;
+                        }
+                        if let Ok(window) = window.lock() {
+                            set_status(&window, &format!("下载中 {}%", percent));
+                        }
+                    })
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => controller.set_status(&format!("已保存到 {}", path.display())),
+                Err(e) => {
+                    error!("Failed to download video {}: {}", id, e);
+                    controller.set_status(&format!("下载失败: {}", e));
+                }
+            }
+        });
+    }
+
+    fn set_status(&self, status: &str) {
+        set_status(&self.window, status);
+    }
+}
+
+fn set_status(window: &Weak<MainWindow>, status: &str) {
+    let status = status.to_string();
+    let _ = window.upgrade_in_event_loop(move |w| {
+        w.set_video_status(status.into());
+    });
+}
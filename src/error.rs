@@ -5,6 +5,7 @@ use std::fmt;
 pub enum AppError {
     Audio(String),
     Common(String),
+    Connection(String),
     Crypto(String),
     Format(String),
     IO(String),
@@ -20,7 +21,11 @@ impl From<reqwest::header::InvalidHeaderValue> for AppError {
 
 impl From<reqwest::Error> for AppError {
     fn from(err: reqwest::Error) -> Self {
-        AppError::Network(err.to_string())
+        if err.is_connect() || err.is_timeout() {
+            AppError::Connection(err.to_string())
+        } else {
+            AppError::Network(err.to_string())
+        }
     }
 }
 
@@ -60,6 +65,12 @@ impl From<rodio::decoder::DecoderError> for AppError {
     }
 }
 
+impl From<symphonia::core::errors::Error> for AppError {
+    fn from(err: symphonia::core::errors::Error) -> Self {
+        AppError::Audio(err.to_string())
+    }
+}
+
 impl From<rodio::source::SeekError> for AppError {
     fn from(err: rodio::source::SeekError) -> Self {
         AppError::Audio(err.to_string())
@@ -95,6 +106,7 @@ impl fmt::Display for AppError {
         match self {
             AppError::Audio(msg) => write!(f, "Audio error: {}", msg),
             AppError::Common(msg) => write!(f, "Common error: {}", msg),
+            AppError::Connection(msg) => write!(f, "Connection error: {}", msg),
             AppError::Crypto(msg) => write!(f, "Crypto error: {}", msg),
             AppError::Format(msg) => write!(f, "Format error: {}", msg),
             AppError::IO(msg) => write!(f, "IO error: {}", msg),
@@ -1,14 +1,28 @@
 slint::include_modules!();
 
 mod audio;
+mod controller;
 mod crash_handler;
 mod error;
 mod models;
 mod network;
 mod service;
 mod storage;
+#[cfg(test)]
+mod testing;
+mod video;
 
 use audio::engine::get_backend;
+use controller::artist::ArtistController;
+use controller::cloud::CloudController;
+use controller::comment::CommentController;
+use controller::playlist::PlaylistController;
+use controller::profile::ProfileController;
+use controller::radio::RadioController;
+use controller::search::SearchController;
+use controller::settings::SettingsController;
+use controller::toplist::ToplistController;
+use controller::video::VideoController;
 use error::AppError;
 use network::device::get_device_id;
 use reqwest::header::{HeaderMap, SET_COOKIE};
@@ -66,5 +80,21 @@ fn main() {
         }
     });
 
+    let _comments = CommentController::new(&main_window, rt.handle().clone());
+    controller::like::bind(&main_window, rt.handle().clone());
+    controller::player::bind(&main_window, rt.handle().clone());
+    controller::recommend::bind(&main_window, rt.handle().clone());
+    controller::similar::bind(&main_window, rt.handle().clone());
+    let _playlists = PlaylistController::new(&main_window, rt.handle().clone());
+    let _artists = ArtistController::new(&main_window, rt.handle().clone());
+    let _toplists = ToplistController::new(&main_window, rt.handle().clone());
+    let _profile = ProfileController::new(&main_window, rt.handle().clone());
+    let _cloud = CloudController::new(&main_window, rt.handle().clone());
+    let _search = SearchController::new(&main_window, rt.handle().clone());
+    let _radio = RadioController::new(&main_window, rt.handle().clone());
+    let _video = VideoController::new(&main_window, rt.handle().clone());
+    let _settings = SettingsController::new(&main_window, rt.handle().clone());
+    service::scrobble::spawn(rt.handle().clone(), get_backend().subscribe());
+
     main_window.run().expect("Failed to run application");
 }
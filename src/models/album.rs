@@ -0,0 +1,48 @@
+use serde::Deserialize;
+
+use crate::models::song::{ArtistRef, Song};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Album {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    pub description: Option<String>,
+    /// 发行时间，毫秒时间戳
+    #[serde(default)]
+    pub publish_time: i64,
+    #[serde(default)]
+    pub company: Option<String>,
+    /// 曲目数
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default, rename = "type")]
+    pub album_type: Option<String>,
+    #[serde(default)]
+    pub artist: ArtistRef,
+    #[serde(default)]
+    pub artists: Vec<ArtistRef>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AlbumDetail {
+    pub album: Album,
+    pub songs: Vec<Song>,
+}
+
+/// 专辑的动态数据，变化频繁，不做缓存
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumDynamic {
+    #[serde(default)]
+    pub is_sub: bool,
+    #[serde(default)]
+    pub sub_count: u64,
+    #[serde(default)]
+    pub comment_count: u64,
+    #[serde(default)]
+    pub liked_count: u64,
+    #[serde(default)]
+    pub share_count: u64,
+}
@@ -0,0 +1,75 @@
+use serde::Deserialize;
+
+use crate::models::album::Album;
+use crate::models::song::Song;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artist {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub alias: Vec<String>,
+    #[serde(default)]
+    pub trans: Option<String>,
+    #[serde(default)]
+    pub brief_desc: Option<String>,
+    #[serde(default)]
+    pub album_size: u64,
+    #[serde(default)]
+    pub music_size: u64,
+    #[serde(default)]
+    pub mv_size: u64,
+    #[serde(default)]
+    pub followed: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IntroSection {
+    #[serde(default, rename = "ti")]
+    pub title: String,
+    #[serde(default, rename = "txt")]
+    pub text: String,
+}
+
+/// 歌手百科
+#[derive(Debug, Clone, Default)]
+pub struct ArtistDescription {
+    pub brief: String,
+    pub sections: Vec<IntroSection>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ArtistDetail {
+    pub artist: Artist,
+    /// 热门歌曲，最多 50 首
+    pub hot_songs: Vec<Song>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtistMv {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    /// 时长，单位毫秒
+    #[serde(default)]
+    pub duration: u64,
+    #[serde(default)]
+    pub play_count: u64,
+    #[serde(default)]
+    pub publish_time: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AlbumPage {
+    pub albums: Vec<Album>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MvPage {
+    pub mvs: Vec<ArtistMv>,
+    pub has_more: bool,
+}
@@ -1,12 +1,387 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::models::dj::ProgramSort;
+use crate::models::dsp::DspSettings;
+use crate::models::song::Song;
+
 #[derive(Debug, Clone)]
 pub enum BackendState {
     Set(String),
-    #[allow(dead_code)]
     Play(bool),
     #[allow(dead_code)]
     Speed(f32),
     #[allow(dead_code)]
     Volume(f32),
-    #[allow(dead_code)]
+    /// 跳到指定位置，单位毫秒；音频还没有加载时会在加载后执行
     Seek(u64),
+    /// 相对当前位置前进或后退，单位毫秒
+    #[allow(dead_code)]
+    SeekBy(i64),
+    /// 替换播放队列并从指定位置开始播放
+    Replace(Vec<Song>, PlayOrigin, usize),
+    /// 追加到播放队列末尾
+    Append(Vec<Song>),
+    /// 切换持续供歌的来源，队列播完前自动向来源请求更多歌曲
+    Source(Option<QueueSource>),
+    Next,
+    Previous,
+    /// 插入到当前歌曲之后
+    PlayNext(Vec<Song>),
+    /// 移除队列中指定位置的歌曲
+    Remove(usize),
+    /// 调整队列顺序，`(from, to)`
+    Move(usize, usize),
+    ClearQueue,
+    /// 跳到队列中指定位置的歌曲
+    Jump(usize),
+    Mode(PlayMode),
+    /// 修改淡入淡出设置并保存
+    Crossfade(CrossfadeConfig),
+    /// 修改响度归一化设置并保存，立即作用于当前曲目
+    #[allow(dead_code)]
+    Normalization(NormalizationConfig),
+    /// 切换输出设备并保存，当前曲目从原来的位置继续播放
+    Output(OutputKind),
+    /// 修改音效设置并保存，不会打断正在播放的曲目
+    Dsp(DspSettings),
+    /// 外部播放器占用期间暂停，全部结束后恢复到之前的播放状态
+    Suspend(bool),
+}
+
+/// 队列播放模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PlayMode {
+    #[default]
+    Sequential,
+    /// 随机顺序由队列保存的种子决定，可以复现
+    Shuffle,
+    RepeatOne,
+    RepeatAll,
+}
+
+/// 淡入淡出的音量曲线
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FadeCurve {
+    Linear,
+    /// 等功率，交叉时总响度基本不变
+    #[default]
+    EqualPower,
+    /// 两端平缓、中间较快
+    SCurve,
+}
+
+impl FadeCurve {
+    /// 淡入进度 `t`（0 到 1）对应的增益，淡出使用 `gain(1 - t)`
+    pub fn gain(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => t,
+            FadeCurve::EqualPower => (t * std::f32::consts::FRAC_PI_2).sin(),
+            FadeCurve::SCurve => (1.0 - (t * std::f32::consts::PI).cos()) / 2.0,
+        }
+    }
+}
+
+/// 音频输出方式，没有声卡时可以改用无声输出或写入文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputKind {
+    /// 按名称选择的输出设备，`None` 为系统默认设备
+    Device(Option<String>),
+    /// 丢弃所有采样，`speed` 为相对实时的倍数
+    Null { speed: u32 },
+    /// 把混音结果写入 WAV 文件
+    Wav { path: PathBuf, speed: u32 },
+}
+
+impl Default for OutputKind {
+    fn default() -> Self {
+        Self::Device(None)
+    }
+}
+
+/// 输出设备支持的一组格式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceConfig {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    pub sample_format: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputDevice {
+    pub name: String,
+    pub is_default: bool,
+    pub configs: Vec<DeviceConfig>,
+}
+
+/// 切歌时的淡入淡出设置，时长为 0 时只做无缝衔接
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CrossfadeConfig {
+    pub duration_ms: u64,
+    pub curve: FadeCurve,
+}
+
+impl CrossfadeConfig {
+    pub const MAX_DURATION_MS: u64 = 12_000;
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms.min(Self::MAX_DURATION_MS))
+    }
+}
+
+/// ReplayGain 2.0 的参考响度，单位 LUFS
+pub const REPLAY_GAIN_REFERENCE: f64 = -18.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NormalizationMode {
+    Off,
+    #[default]
+    Track,
+    /// 同一专辑内保持原有的响度差异，没有专辑增益时使用单曲增益
+    Album,
+}
+
+/// 响度归一化设置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NormalizationConfig {
+    pub mode: NormalizationMode,
+    /// 在归一化增益之上额外的增益，单位 dB
+    pub preamp_db: f32,
+    /// 根据峰值限制增益，避免削波
+    pub prevent_clipping: bool,
+}
+
+impl Default for NormalizationConfig {
+    fn default() -> Self {
+        Self {
+            mode: NormalizationMode::default(),
+            preamp_db: 0.0,
+            prevent_clipping: true,
+        }
+    }
+}
+
+/// 曲目的增益信息，增益单位为 dB，峰值为线性幅度
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReplayGain {
+    pub track_gain: f32,
+    pub track_peak: Option<f32>,
+    pub album_gain: Option<f32>,
+    pub album_peak: Option<f32>,
+}
+
+impl ReplayGain {
+    /// 由本地测量的单曲与专辑响度得到增益
+    pub fn from_loudness(track: Loudness, album: Option<Loudness>) -> Self {
+        Self {
+            track_gain: track.gain(),
+            track_peak: Some(track.peak),
+            album_gain: album.map(|album| album.gain()),
+            album_peak: album.map(|album| album.peak),
+        }
+    }
+
+    /// 按设置计算应用到采样上的线性倍数
+    pub fn factor(&self, config: &NormalizationConfig) -> f32 {
+        let (gain, peak) = match config.mode {
+            NormalizationMode::Off => return 1.0,
+            NormalizationMode::Track => (self.track_gain, self.track_peak),
+            NormalizationMode::Album => match self.album_gain {
+                Some(gain) => (gain, self.album_peak.or(self.track_peak)),
+                None => (self.track_gain, self.track_peak),
+            },
+        };
+
+        let factor = 10f32.powf((gain + config.preamp_db) / 20.0);
+        match peak.filter(|peak| *peak > 0.0) {
+            Some(peak) if config.prevent_clipping => factor.min(1.0 / peak),
+            _ => factor,
+        }
+    }
+}
+
+/// EBU R128 测量结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Loudness {
+    /// 积分响度，单位 LUFS
+    pub integrated: f64,
+    /// 采样峰值
+    pub peak: f32,
+}
+
+impl Loudness {
+    /// 达到 ReplayGain 参考响度所需的增益
+    pub fn gain(&self) -> f32 {
+        (REPLAY_GAIN_REFERENCE - self.integrated) as f32
+    }
+}
+
+/// 可以无限续播的歌曲来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueSource {
+    PersonalFm,
+    /// 心动模式：以一首喜欢的歌为种子，在指定歌单中推荐
+    Intelligence {
+        song_id: u64,
+        playlist_id: u64,
+    },
+    /// 歌曲电台：由相似歌曲不断扩展
+    SongRadio {
+        song_id: u64,
+    },
+    /// 按顺序播放电台节目
+    DjRadio {
+        radio_id: u64,
+        sort: ProgramSort,
+    },
+}
+
+/// 播放来源，用于向网易云上报听歌记录
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum PlayOrigin {
+    Playlist(u64),
+    Album(u64),
+    Artist(u64),
+    Toplist(u64),
+    DailyRecommend,
+    PersonalFm,
+    Intelligence(u64),
+    Search,
+    DjRadio(u64),
+    SongRadio,
+    #[default]
+    Unknown,
+}
+
+impl From<&QueueSource> for PlayOrigin {
+    fn from(source: &QueueSource) -> Self {
+        match source {
+            QueueSource::PersonalFm => PlayOrigin::PersonalFm,
+            QueueSource::Intelligence { playlist_id, .. } => PlayOrigin::Intelligence(*playlist_id),
+            QueueSource::DjRadio { radio_id, .. } => PlayOrigin::DjRadio(*radio_id),
+            QueueSource::SongRadio { .. } => PlayOrigin::SongRadio,
+        }
+    }
+}
+
+impl PlayOrigin {
+    /// weblog 中 `source` 字段的取值
+    pub fn source_name(&self) -> &'static str {
+        match self {
+            PlayOrigin::Playlist(_) | PlayOrigin::Intelligence(_) => "list",
+            PlayOrigin::Album(_) => "album",
+            PlayOrigin::Artist(_) => "artist",
+            PlayOrigin::Toplist(_) => "toplist",
+            PlayOrigin::DailyRecommend => "dailySongRecommend",
+            PlayOrigin::PersonalFm => "userfm",
+            PlayOrigin::Search => "search",
+            PlayOrigin::DjRadio(_) => "djradio",
+            PlayOrigin::SongRadio => "list",
+            PlayOrigin::Unknown => "list",
+        }
+    }
+
+    pub fn source_id(&self) -> String {
+        match self {
+            PlayOrigin::Playlist(id)
+            | PlayOrigin::Album(id)
+            | PlayOrigin::Artist(id)
+            | PlayOrigin::Toplist(id)
+            | PlayOrigin::Intelligence(id)
+            | PlayOrigin::DjRadio(id) => id.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// 电台节目需要记录收听进度
+    pub fn is_program(&self) -> bool {
+        matches!(self, PlayOrigin::DjRadio(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndReason {
+    /// 自然播放结束
+    Finished,
+    /// 用户切歌
+    Skipped,
+    /// 播放出错或程序退出
+    Interrupted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaybackState {
+    #[default]
+    Stopped,
+    /// 正在获取播放地址或预加载
+    Loading,
+    Playing,
+    Paused,
+}
+
+/// 播放器当前状态的快照
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default)]
+pub struct PlayerStatus {
+    pub state: PlaybackState,
+    pub song: Option<Song>,
+    pub origin: PlayOrigin,
+    pub position: Duration,
+    pub duration: Option<Duration>,
+    /// 从播放位置起连续缓冲到的字节位置与文件大小
+    pub buffered: u64,
+    pub total_bytes: Option<u64>,
+    /// 连续缓冲到的位置占总长度的百分比，长度未知时为 `None`
+    pub buffered_percent: Option<f32>,
+    /// 已缓存的全部字节范围
+    pub buffered_ranges: Vec<Range<u64>>,
+    pub volume: f32,
+    pub speed: f32,
+    pub mode: PlayMode,
+    pub queue: Arc<[Song]>,
+    pub queue_index: Option<usize>,
+    /// 当前输出设备的名称，无声输出为 "null"，WAV 输出为文件路径
+    pub output: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum PlayerEvent {
+    StateChanged(PlaybackState),
+    TrackStarted {
+        song: Song,
+        origin: PlayOrigin,
+    },
+    /// 播放中定期发送
+    Position {
+        position: Duration,
+        duration: Option<Duration>,
+    },
+    /// 缓冲进度有变化，`percent` 为连续缓冲到的位置占总长度的百分比
+    Buffering {
+        percent: Option<f32>,
+    },
+    /// 获取地址、加载或解码失败
+    Error(String),
+    /// 队列内容、当前位置或播放模式有变化
+    QueueChanged {
+        songs: Arc<[Song]>,
+        index: Option<usize>,
+        mode: PlayMode,
+    },
+    /// 切换了输出设备，`fallback` 表示原设备不可用，自动改用了其他设备
+    OutputChanged {
+        device: String,
+        fallback: bool,
+    },
+    TrackEnded {
+        song: Song,
+        origin: PlayOrigin,
+        listened: Duration,
+        reason: EndReason,
+    },
 }
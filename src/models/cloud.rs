@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::song::Song;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudSong {
+    pub song_id: u64,
+    #[serde(default)]
+    pub song_name: String,
+    #[serde(default)]
+    pub file_name: String,
+    #[serde(default)]
+    pub file_size: u64,
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub album: String,
+    #[serde(default)]
+    pub bitrate: u64,
+    /// 匹配到的官方曲目信息，未匹配时为云盘自身的歌曲信息
+    pub simple_song: Option<Song>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CloudPage {
+    pub songs: Vec<CloudSong>,
+    pub count: u64,
+    /// 已用空间，单位字节
+    pub size: u64,
+    pub max_size: u64,
+    pub has_more: bool,
+}
+
+/// 上传时写入云盘的歌曲信息，缺省时使用文件名
+#[derive(Debug, Clone, Default)]
+pub struct CloudSongMeta {
+    pub song: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub bitrate: Option<u64>,
+}
+
+/// NOS 分片上传的进度，保存在本地以便中断后续传
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub bucket: String,
+    pub object_key: String,
+    pub token: String,
+    pub resource_id: u64,
+    pub host: String,
+    pub context: Option<String>,
+    pub offset: u64,
+}
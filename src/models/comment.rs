@@ -0,0 +1,140 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentSort {
+    Recommend,
+    Hot,
+    Time,
+}
+
+impl CommentSort {
+    pub fn code(self) -> u8 {
+        match self {
+            CommentSort::Recommend => 99,
+            CommentSort::Hot => 2,
+            CommentSort::Time => 3,
+        }
+    }
+
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            2 => CommentSort::Hot,
+            3 => CommentSort::Time,
+            _ => CommentSort::Recommend,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentUser {
+    pub user_id: u64,
+    #[serde(default)]
+    pub nickname: String,
+    #[serde(default)]
+    pub avatar_url: String,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BeReplied {
+    #[serde(default)]
+    pub user: CommentUser,
+    #[serde(default)]
+    pub be_replied_comment_id: u64,
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FloorSummary {
+    #[serde(default)]
+    pub reply_count: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    pub comment_id: u64,
+    /// 被删除的评论内容为空
+    pub content: Option<String>,
+    #[serde(default)]
+    pub time: u64,
+    #[serde(default)]
+    pub time_str: Option<String>,
+    #[serde(default)]
+    pub liked_count: u64,
+    #[serde(default)]
+    pub liked: bool,
+    #[serde(default)]
+    pub user: CommentUser,
+    #[serde(default)]
+    pub be_replied: Vec<BeReplied>,
+    #[serde(default)]
+    pub parent_comment_id: u64,
+    #[serde(default)]
+    pub show_floor_comment: Option<FloorSummary>,
+}
+
+impl Comment {
+    pub fn reply_count(&self) -> u64 {
+        self.show_floor_comment
+            .as_ref()
+            .map(|f| f.reply_count)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommentPage {
+    pub comments: Vec<Comment>,
+    pub total: u64,
+    pub has_more: bool,
+    /// 下一页的游标；按时间排序时由服务端给出，楼层回复中为最后一条的时间戳
+    pub cursor: Option<String>,
+}
+
+/// 评论分页参数，通过 [`CommentPaging::next`] 从上一页结果推导下一页
+#[derive(Debug, Clone)]
+pub struct CommentPaging {
+    pub sort: CommentSort,
+    pub page_no: u32,
+    pub page_size: u32,
+    pub cursor: Option<String>,
+}
+
+impl CommentPaging {
+    pub fn new(sort: CommentSort) -> Self {
+        Self {
+            sort,
+            page_no: 1,
+            page_size: 20,
+            cursor: None,
+        }
+    }
+
+    pub fn next(&self, page: &CommentPage) -> Self {
+        Self {
+            sort: self.sort,
+            page_no: self.page_no + 1,
+            page_size: self.page_size,
+            cursor: page.cursor.clone(),
+        }
+    }
+
+    pub fn offset(&self) -> u32 {
+        self.page_no.saturating_sub(1) * self.page_size
+    }
+
+    pub fn cursor_value(&self) -> String {
+        let offset = self.offset().to_string();
+        match self.sort {
+            CommentSort::Recommend => offset,
+            CommentSort::Hot => format!("normalHot#{}", offset),
+            CommentSort::Time => self.cursor.clone().unwrap_or_else(|| "0".to_string()),
+        }
+    }
+}
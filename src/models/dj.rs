@@ -0,0 +1,74 @@
+use serde::Deserialize;
+
+use crate::models::comment::CommentUser;
+use crate::models::song::Song;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DjCategory {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DjRadio {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub category_id: u64,
+    #[serde(default)]
+    pub program_count: u64,
+    #[serde(default)]
+    pub sub_count: u64,
+    #[serde(default)]
+    pub sub_ed: bool,
+    pub dj: Option<CommentUser>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DjProgram {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    /// 时长，单位毫秒
+    #[serde(default)]
+    pub duration: u64,
+    #[serde(default)]
+    pub create_time: i64,
+    /// 节目在电台中的期数
+    #[serde(default)]
+    pub serial_num: u32,
+    #[serde(default)]
+    pub listener_count: u64,
+    /// 节目音频，通过歌曲接口获取播放地址
+    pub main_song: Song,
+    pub radio: Option<DjRadio>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProgramPage {
+    pub programs: Vec<DjProgram>,
+    pub has_more: bool,
+}
+
+/// 节目列表排序，默认从最新一期开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgramSort {
+    #[default]
+    Newest,
+    Oldest,
+}
+
+impl ProgramSort {
+    pub fn is_asc(self) -> bool {
+        self == ProgramSort::Oldest
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// 图示均衡器各频段的中心频率，单位 Hz
+pub const EQ_FREQUENCIES: [f32; 10] = [
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+/// 倍频程间隔对应的 Q 值
+const OCTAVE_Q: f32 = std::f32::consts::SQRT_2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FilterKind {
+    LowShelf,
+    #[default]
+    Peaking,
+    HighShelf,
+}
+
+/// 均衡器的一个频段
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EqBand {
+    pub kind: FilterKind,
+    pub frequency: f32,
+    pub gain_db: f32,
+    pub q: f32,
+}
+
+impl EqBand {
+    pub fn peaking(frequency: f32, gain_db: f32) -> Self {
+        Self {
+            kind: FilterKind::Peaking,
+            frequency,
+            gain_db,
+            q: OCTAVE_Q,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EqPreset {
+    pub name: String,
+    pub bands: Vec<EqBand>,
+}
+
+impl EqPreset {
+    /// 由图示均衡器各频段的增益创建
+    pub fn graphic(name: &str, gains: [f32; 10]) -> Self {
+        Self {
+            name: name.to_string(),
+            bands: EQ_FREQUENCIES
+                .iter()
+                .zip(gains)
+                .map(|(frequency, gain)| EqBand::peaking(*frequency, gain))
+                .collect(),
+        }
+    }
+
+    /// 内置预设
+    pub fn builtin() -> Vec<EqPreset> {
+        vec![
+            Self::graphic("平直", [0.0; 10]),
+            Self::graphic(
+                "流行",
+                [-1.0, 1.0, 3.0, 4.0, 3.0, 0.0, -1.0, -1.0, 0.0, 1.0],
+            ),
+            Self::graphic("摇滚", [5.0, 4.0, 3.0, 1.0, -1.0, -1.0, 1.0, 3.0, 4.0, 5.0]),
+            Self::graphic("爵士", [3.0, 2.0, 1.0, 2.0, -1.0, -1.0, 0.0, 1.0, 2.0, 3.0]),
+            Self::graphic("古典", [4.0, 3.0, 2.0, 1.0, -1.0, -1.0, 0.0, 2.0, 3.0, 4.0]),
+            Self::graphic("电子", [5.0, 4.0, 1.0, 0.0, -2.0, 2.0, 1.0, 1.0, 4.0, 5.0]),
+            Self::graphic(
+                "人声",
+                [-2.0, -3.0, -3.0, 1.0, 4.0, 4.0, 3.0, 1.0, 0.0, -2.0],
+            ),
+            Self::graphic(
+                "低音增强",
+                [6.0, 5.0, 4.0, 2.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            ),
+            Self::graphic(
+                "高音增强",
+                [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 4.0, 5.0, 6.0],
+            ),
+        ]
+    }
+}
+
+/// 音效设置，播放中修改会立即生效
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DspSettings {
+    pub eq_enabled: bool,
+    pub bands: Vec<EqBand>,
+    /// 单位 dB，用于抵消均衡器提升带来的削波
+    pub preamp_db: f32,
+    /// 左右声道平衡，-1 为只有左声道，1 为只有右声道
+    pub balance: f32,
+    /// 混合为单声道
+    pub mono: bool,
+}
+
+impl Default for DspSettings {
+    fn default() -> Self {
+        Self {
+            eq_enabled: false,
+            bands: EqPreset::graphic("", [0.0; 10]).bands,
+            preamp_db: 0.0,
+            balance: 0.0,
+            mono: false,
+        }
+    }
+}
+
+impl DspSettings {
+    /// 使用预设的频段并开启均衡器
+    pub fn apply_preset(&mut self, preset: &EqPreset) {
+        self.bands = preset.bands.clone();
+        self.eq_enabled = true;
+    }
+}
@@ -1,6 +1,9 @@
 use reqwest::header::HeaderMap;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
+use crate::error::AppError;
+
 #[derive(Default, Debug)]
 pub struct RequestOption {
     pub crypto: Option<String>,
@@ -11,14 +14,48 @@ pub struct RequestOption {
     pub e_r: Option<bool>,
     pub ua: Option<String>,
     pub cache: Option<bool>,
+    /// 替换默认的接口域名，如 `http://127.0.0.1:3000`
+    pub domain: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Response {
-    #[allow(dead_code)]
     pub status: u16,
     pub body: Value,
     pub cookie: Option<Vec<String>>,
     #[allow(dead_code)]
     pub headers: HeaderMap,
 }
+
+impl Response {
+    /// 接口返回的业务状态码，缺失时回退为 HTTP 状态码
+    pub fn code(&self) -> i64 {
+        self.body
+            .get("code")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(self.status as i64)
+    }
+
+    /// 业务状态码不为 200 时转换为错误
+    pub fn ensure_success(self) -> Result<Self, AppError> {
+        match self.code() {
+            200 => Ok(self),
+            code => {
+                let message = ["message", "msg"]
+                    .iter()
+                    .find_map(|key| self.body.get(*key).and_then(|v| v.as_str()))
+                    .unwrap_or("unknown error");
+                Err(AppError::Network(format!("API code {}: {}", code, message)))
+            }
+        }
+    }
+
+    /// 将 `pointer` 指向的字段反序列化为目标类型，如 `/data/comments`
+    pub fn parse<T: DeserializeOwned>(&self, pointer: &str) -> Result<T, AppError> {
+        let value = self
+            .body
+            .pointer(pointer)
+            .ok_or_else(|| AppError::Format(format!("Missing field in response: {}", pointer)))?;
+        Ok(T::deserialize(value)?)
+    }
+}
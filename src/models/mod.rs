@@ -1,2 +1,15 @@
+pub mod album;
+pub mod artist;
 pub mod audio;
+pub mod cloud;
+pub mod comment;
+pub mod dj;
+pub mod dsp;
 pub mod http;
+pub mod playlist;
+pub mod resource;
+pub mod search;
+pub mod song;
+pub mod toplist;
+pub mod user;
+pub mod video;
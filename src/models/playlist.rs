@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistPrivacy {
+    Public,
+    Private,
+}
+
+impl PlaylistPrivacy {
+    pub fn code(self) -> u8 {
+        match self {
+            PlaylistPrivacy::Public => 0,
+            PlaylistPrivacy::Private => 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistCreator {
+    #[serde(default)]
+    pub nickname: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Playlist {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub cover_img_url: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub track_count: u64,
+    #[serde(default)]
+    pub play_count: u64,
+    #[serde(default)]
+    pub privacy: u8,
+    #[serde(default)]
+    pub subscribed: Option<bool>,
+    #[serde(default)]
+    pub user_id: u64,
+    pub creator: Option<PlaylistCreator>,
+    /// 5 为"我喜欢的音乐"
+    #[serde(default)]
+    pub special_type: u32,
+}
+
+impl Playlist {
+    pub fn privacy(&self) -> PlaylistPrivacy {
+        if self.privacy == PlaylistPrivacy::Private.code() {
+            PlaylistPrivacy::Private
+        } else {
+            PlaylistPrivacy::Public
+        }
+    }
+
+    pub fn is_liked_playlist(&self) -> bool {
+        self.special_type == 5
+    }
+}
@@ -1,22 +1,110 @@
-use std::collections::HashMap;
-use std::sync::OnceLock;
-
-static RESOURCE_TYPE_MAP: OnceLock<HashMap<String, String>> = OnceLock::new();
-
-pub fn get_resource_type_map() -> &'static HashMap<String, String> {
-    RESOURCE_TYPE_MAP.get_or_init(|| {
-        [
-            ("0", "R_SO_4_"),
-            ("1", "R_MV_5_"),
-            ("2", "A_PL_0_"),
-            ("3", "R_AL_3_"),
-            ("4", "A_DJ_1_"),
-            ("5", "R_VI_62_"),
-            ("6", "A_EV_2_"),
-            ("7", "A_DR_14_"),
-        ]
-        .iter()
-        .map(|(k, v)| (k.to_string(), v.to_string()))
-        .collect()
-    })
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceType {
+    Song,
+    MV,
+    Playlist,
+    Album,
+    DjProgram,
+    Video,
+    Event,
+    Dr,
+}
+
+impl ResourceType {
+    const ALL: [ResourceType; 8] = [
+        ResourceType::Song,
+        ResourceType::MV,
+        ResourceType::Playlist,
+        ResourceType::Album,
+        ResourceType::DjProgram,
+        ResourceType::Video,
+        ResourceType::Event,
+        ResourceType::Dr,
+    ];
+
+    /// 网易云接口中 `type` 字段使用的数字编号
+    pub fn code(self) -> u8 {
+        match self {
+            ResourceType::Song => 0,
+            ResourceType::MV => 1,
+            ResourceType::Playlist => 2,
+            ResourceType::Album => 3,
+            ResourceType::DjProgram => 4,
+            ResourceType::Video => 5,
+            ResourceType::Event => 6,
+            ResourceType::Dr => 7,
+        }
+    }
+
+    pub fn from_code(code: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.code() == code)
+    }
+
+    /// 评论区等接口使用的 threadId 前缀
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ResourceType::Song => "R_SO_4_",
+            ResourceType::MV => "R_MV_5_",
+            ResourceType::Playlist => "A_PL_0_",
+            ResourceType::Album => "R_AL_3_",
+            ResourceType::DjProgram => "A_DJ_1_",
+            ResourceType::Video => "R_VI_62_",
+            ResourceType::Event => "A_EV_2_",
+            ResourceType::Dr => "A_DR_14_",
+        }
+    }
+
+    pub fn thread_id(self, id: impl fmt::Display) -> String {
+        format!("{}{}", self.prefix(), id)
+    }
+
+    /// 将 `R_SO_4_123` 这样的 threadId 拆分为资源类型与资源ID
+    pub fn parse_thread_id(thread_id: &str) -> Option<(Self, String)> {
+        Self::ALL.into_iter().find_map(|t| {
+            thread_id
+                .strip_prefix(t.prefix())
+                .filter(|id| !id.is_empty())
+                .map(|id| (t, id.to_string()))
+        })
+    }
+}
+
+impl TryFrom<u8> for ResourceType {
+    type Error = AppError;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        Self::from_code(code)
+            .ok_or_else(|| AppError::Format(format!("Unknown resource type code: {}", code)))
+    }
+}
+
+impl From<ResourceType> for u8 {
+    fn from(value: ResourceType) -> Self {
+        value.code()
+    }
+}
+
+impl FromStr for ResourceType {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(code) = s.parse::<u8>() {
+            return Self::try_from(code);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|t| t.prefix() == s)
+            .ok_or_else(|| AppError::Format(format!("Unknown resource type: {}", s)))
+    }
+}
+
+impl fmt::Display for ResourceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.prefix())
+    }
 }
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+
+use crate::models::album::Album;
+use crate::models::artist::Artist;
+use crate::models::playlist::Playlist;
+use crate::models::song::Song;
+
+/// 输入过程中的搜索建议，按 `order` 中的类型顺序展示
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchSuggest {
+    #[serde(default)]
+    pub songs: Vec<Song>,
+    #[serde(default)]
+    pub artists: Vec<Artist>,
+    #[serde(default)]
+    pub albums: Vec<Album>,
+    #[serde(default)]
+    pub playlists: Vec<Playlist>,
+    #[serde(default)]
+    pub order: Vec<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotSearch {
+    pub search_word: String,
+    #[serde(default)]
+    pub score: u64,
+    /// 热搜说明，可能为空
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub icon_url: Option<String>,
+}
+
+/// 搜索框默认显示的关键词
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultKeyword {
+    #[serde(default)]
+    pub show_keyword: String,
+    /// 实际用于搜索的关键词
+    #[serde(default)]
+    pub realkeyword: String,
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::audio::ReplayGain;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtistRef {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumRef {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub pic_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Song {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    // 私人FM等旧接口使用完整字段名
+    #[serde(default, rename = "ar", alias = "artists")]
+    pub artists: Vec<ArtistRef>,
+    #[serde(default, rename = "al", alias = "album")]
+    pub album: AlbumRef,
+    /// 时长，单位毫秒
+    #[serde(default, rename = "dt", alias = "duration")]
+    pub duration: u64,
+    #[serde(default)]
+    pub fee: u32,
+    #[serde(default)]
+    pub mv: u64,
+    #[serde(default)]
+    pub no: u32,
+    #[serde(default)]
+    pub cd: Option<String>,
+}
+
+impl Song {
+    pub fn artist_names(&self) -> String {
+        self.artists
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrackId {
+    pub id: u64,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundQuality {
+    Standard,
+    Higher,
+    ExHigh,
+    Lossless,
+    HiRes,
+}
+
+impl SoundQuality {
+    pub fn level(self) -> &'static str {
+        match self {
+            SoundQuality::Standard => "standard",
+            SoundQuality::Higher => "higher",
+            SoundQuality::ExHigh => "exhigh",
+            SoundQuality::Lossless => "lossless",
+            SoundQuality::HiRes => "hires",
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SongUrl {
+    pub id: u64,
+    /// 无版权或需要付费时为空
+    pub url: Option<String>,
+    #[serde(default)]
+    pub br: u64,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default, rename = "type")]
+    pub format: Option<String>,
+    /// 网易云提供的响度增益，单位 dB
+    #[serde(default)]
+    pub gain: Option<f32>,
+    #[serde(default)]
+    pub peak: Option<f32>,
+}
+
+impl SongUrl {
+    /// 服务端没有响度数据时增益与峰值均为 0
+    pub fn replay_gain(&self) -> Option<ReplayGain> {
+        let peak = self.peak.filter(|peak| *peak > 0.0)?;
+        Some(ReplayGain {
+            track_gain: self.gain?,
+            track_peak: Some(peak),
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::song::Song;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Toplist {
+    /// 榜单即歌单，可按歌单加载曲目
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub cover_img_url: String,
+    pub description: Option<String>,
+    /// 如"每周四更新"
+    #[serde(default)]
+    pub update_frequency: String,
+    /// 曲目最近一次更新的毫秒时间戳
+    #[serde(default)]
+    pub track_update_time: u64,
+    /// 官方榜单的类型代码，全球榜为空
+    #[serde(default, rename = "ToplistType")]
+    pub toplist_type: Option<String>,
+}
+
+impl Toplist {
+    pub fn is_official(&self) -> bool {
+        self.toplist_type.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Toplists {
+    pub official: Vec<Toplist>,
+    pub global: Vec<Toplist>,
+}
+
+/// 上次查看榜单时的曲目，用于对比变化
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChartSnapshot {
+    pub update_time: u64,
+    pub track_ids: Vec<u64>,
+}
+
+/// 与上次查看相比的变化，首次查看时两者均为空
+#[derive(Debug, Clone, Default)]
+pub struct ChartDiff {
+    pub first_view: bool,
+    /// 新上榜的歌曲，保持榜单内顺序
+    pub new_entries: Vec<u64>,
+    pub dropped: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartDetail {
+    pub toplist: Toplist,
+    pub tracks: Vec<Song>,
+    pub diff: ChartDiff,
+}
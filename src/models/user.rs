@@ -0,0 +1,89 @@
+use serde::Deserialize;
+
+use crate::models::playlist::Playlist;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Gender {
+    #[default]
+    Unknown,
+    Male,
+    Female,
+}
+
+impl Gender {
+    pub fn code(self) -> u8 {
+        match self {
+            Gender::Unknown => 0,
+            Gender::Male => 1,
+            Gender::Female => 2,
+        }
+    }
+
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            1 => Gender::Male,
+            2 => Gender::Female,
+            _ => Gender::Unknown,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub user_id: u64,
+    #[serde(default)]
+    pub nickname: String,
+    #[serde(default)]
+    pub avatar_url: String,
+    #[serde(default)]
+    pub background_url: String,
+    pub signature: Option<String>,
+    #[serde(default)]
+    pub gender: i64,
+    /// 毫秒时间戳，未设置时为负数
+    #[serde(default)]
+    pub birthday: i64,
+    #[serde(default)]
+    pub province: u64,
+    #[serde(default)]
+    pub city: u64,
+    #[serde(default)]
+    pub follows: u64,
+    #[serde(default)]
+    pub followeds: u64,
+    #[serde(default)]
+    pub followed: bool,
+    #[serde(default)]
+    pub playlist_count: u64,
+}
+
+impl UserProfile {
+    pub fn gender(&self) -> Gender {
+        Gender::from_code(self.gender)
+    }
+}
+
+/// 用户主页信息
+#[derive(Debug, Clone, Default)]
+pub struct UserDetail {
+    pub profile: UserProfile,
+    pub level: u32,
+    /// 累计听歌数
+    pub listen_songs: u64,
+    pub created_playlists: Vec<Playlist>,
+    pub subscribed_playlists: Vec<Playlist>,
+}
+
+/// 修改账号资料，未设置的字段沿用当前资料
+#[derive(Debug, Clone, Default)]
+pub struct ProfileUpdate {
+    pub nickname: Option<String>,
+    pub signature: Option<String>,
+    pub gender: Option<Gender>,
+    /// 毫秒时间戳
+    pub birthday: Option<i64>,
+    pub province: Option<u64>,
+    pub city: Option<u64>,
+}
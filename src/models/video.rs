@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+/// 视频清晰度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Resolution {
+    P240,
+    P480,
+    P720,
+    P1080,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 4] = [
+        Resolution::P240,
+        Resolution::P480,
+        Resolution::P720,
+        Resolution::P1080,
+    ];
+
+    /// 不超过 `max` 的最高可用清晰度，都超过时取最低的可用清晰度
+    pub fn best(available: &[u32], max: Resolution) -> Resolution {
+        let supported: Vec<Resolution> = Self::ALL
+            .into_iter()
+            .filter(|r| available.contains(&r.value()))
+            .collect();
+        supported
+            .iter()
+            .rfind(|r| **r <= max)
+            .or(supported.first())
+            .copied()
+            .unwrap_or(max)
+    }
+
+    pub fn value(self) -> u32 {
+        match self {
+            Resolution::P240 => 240,
+            Resolution::P480 => 480,
+            Resolution::P720 => 720,
+            Resolution::P1080 => 1080,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MvBitrate {
+    pub br: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MvDetail {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub artist_name: String,
+    /// 可用的清晰度
+    #[serde(default)]
+    pub brs: Vec<MvBitrate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoResolution {
+    pub resolution: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoDetail {
+    /// 视频ID为字符串
+    pub vid: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub resolutions: Vec<VideoResolution>,
+}
+
+/// 解析后的播放地址
+#[derive(Debug, Clone)]
+pub struct VideoStream {
+    pub title: String,
+    pub url: String,
+    /// 实际返回的清晰度，可能低于请求的清晰度
+    pub resolution: u32,
+    pub size: u64,
+}
+
+/// 外部视频播放器的启动命令
+///
+/// 参数中的 `{url}`、`{title}`、`{referer}`、`{user_agent}` 会被替换
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoPlayerConfig {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl Default for VideoPlayerConfig {
+    fn default() -> Self {
+        Self {
+            program: "mpv".to_string(),
+            args: vec![
+                "--force-media-title={title}".to_string(),
+                "--referrer={referer}".to_string(),
+                "--user-agent={user_agent}".to_string(),
+                "{url}".to_string(),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Resolution;
+
+    #[test]
+    fn picks_best_available_resolution() {
+        let available = [240, 480, 720];
+        assert_eq!(
+            Resolution::best(&available, Resolution::P1080),
+            Resolution::P720
+        );
+        assert_eq!(
+            Resolution::best(&available, Resolution::P480),
+            Resolution::P480
+        );
+        assert_eq!(
+            Resolution::best(&[720, 1080], Resolution::P240),
+            Resolution::P720
+        );
+        assert_eq!(Resolution::best(&[], Resolution::P720), Resolution::P720);
+    }
+}
@@ -39,9 +39,15 @@ fn build_request_url_and_data(
     crypto: &str,
     option: &RequestOption,
 ) -> Result<(String, Value), AppError> {
+    let music_domain = option.domain.as_deref().unwrap_or("https://music.163.com");
+    let interface_domain = option
+        .domain
+        .as_deref()
+        .unwrap_or("https://interface.music.163.com");
+
     match crypto {
         "weapi" => Ok((
-            format!("https://music.163.com/weapi/{}", &uri[5..]),
+            format!("{}/weapi/{}", music_domain, &uri[5..]),
             weapi(&data)?,
         )),
         "linuxapi" => {
@@ -51,7 +57,7 @@ fn build_request_url_and_data(
                 "params": data
             });
             Ok((
-                "https://music.163.com/api/linux/forward".to_string(),
+                format!("{}/api/linux/forward", music_domain),
                 eapi(uri, &unencrypted_data)?,
             ))
         }
@@ -62,11 +68,11 @@ fn build_request_url_and_data(
                 .unwrap_or_default()
                 .into();
             Ok((
-                format!("https://interface.music.163.com/eapi/{}", &uri[5..]),
+                format!("{}/eapi/{}", interface_domain, &uri[5..]),
                 eapi(uri, &data)?,
             ))
         }
-        "api" => Ok((format!("https://interface.music.163.com{}", uri), data)),
+        "api" => Ok((format!("{}{}", interface_domain, uri), data)),
         _ => Err(AppError::Crypto(format!("Unsupported crypto: {}", crypto))),
     }
 }
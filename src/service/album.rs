@@ -0,0 +1,65 @@
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::album::{Album, AlbumDetail, AlbumDynamic};
+use crate::models::http::RequestOption;
+use crate::network::request::create_request;
+use crate::storage::cache::invalidate;
+
+const SUBSCRIBED_ALBUMS_URI: &str = "/api/album/sublist";
+
+fn option(cache: bool) -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(cache),
+        ..Default::default()
+    }
+}
+
+/// 获取专辑信息及全部曲目，歌曲中的 `album` 可用于跳转到这里
+pub async fn get_album(id: u64) -> Result<AlbumDetail, AppError> {
+    let response = create_request(&format!("/api/v1/album/{}", id), json!({}), option(true))
+        .await?
+        .ensure_success()?;
+
+    Ok(AlbumDetail {
+        album: response.parse("/album")?,
+        songs: response.parse("/songs").unwrap_or_default(),
+    })
+}
+
+/// 评论数、分享数与收藏状态等动态数据
+pub async fn get_album_dynamic(id: u64) -> Result<AlbumDynamic, AppError> {
+    create_request(
+        "/api/album/detail/dynamic",
+        json!({ "id": id }),
+        option(false),
+    )
+    .await?
+    .ensure_success()?
+    .parse("")
+}
+
+/// 收藏或取消收藏专辑
+pub async fn subscribe_album(id: u64, subscribe: bool) -> Result<(), AppError> {
+    let uri = if subscribe {
+        "/api/album/sub"
+    } else {
+        "/api/album/unsub"
+    };
+
+    create_request(uri, json!({ "id": id }), option(false))
+        .await?
+        .ensure_success()?;
+    invalidate(&[SUBSCRIBED_ALBUMS_URI]);
+    Ok(())
+}
+
+/// 已收藏的专辑
+pub async fn get_subscribed_albums(limit: u32, offset: u32) -> Result<Vec<Album>, AppError> {
+    let data = json!({ "limit": limit, "offset": offset, "total": true });
+    create_request(SUBSCRIBED_ALBUMS_URI, data, option(true))
+        .await?
+        .ensure_success()?
+        .parse("/data")
+}
@@ -0,0 +1,117 @@
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::artist::{AlbumPage, Artist, ArtistDescription, ArtistDetail, MvPage};
+use crate::models::http::RequestOption;
+use crate::network::request::create_request;
+use crate::storage::cache::invalidate;
+
+const FOLLOWED_ARTISTS_URI: &str = "/api/artist/sublist";
+
+fn option(cache: bool) -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(cache),
+        ..Default::default()
+    }
+}
+
+fn artist_uri(id: u64) -> String {
+    format!("/api/v1/artist/{}", id)
+}
+
+/// 获取歌手信息及热门歌曲，歌曲中的 `artists` 可用于跳转到这里
+pub async fn get_artist(id: u64) -> Result<ArtistDetail, AppError> {
+    let response = create_request(&artist_uri(id), json!({}), option(true))
+        .await?
+        .ensure_success()?;
+
+    Ok(ArtistDetail {
+        artist: response.parse("/artist")?,
+        hot_songs: response.parse("/hotSongs").unwrap_or_default(),
+    })
+}
+
+pub async fn get_description(id: u64) -> Result<ArtistDescription, AppError> {
+    let response = create_request(
+        "/api/artist/introduction",
+        json!({ "id": id }),
+        option(true),
+    )
+    .await?
+    .ensure_success()?;
+
+    Ok(ArtistDescription {
+        brief: response.parse("/briefDesc").unwrap_or_default(),
+        sections: response.parse("/introduction").unwrap_or_default(),
+    })
+}
+
+pub async fn get_albums(id: u64, limit: u32, offset: u32) -> Result<AlbumPage, AppError> {
+    let data = json!({ "limit": limit, "offset": offset, "total": true });
+    let response = create_request(&format!("/api/artist/albums/{}", id), data, option(true))
+        .await?
+        .ensure_success()?;
+
+    Ok(AlbumPage {
+        albums: response.parse("/hotAlbums")?,
+        has_more: response.parse("/more").unwrap_or_default(),
+    })
+}
+
+pub async fn get_mvs(id: u64, limit: u32, offset: u32) -> Result<MvPage, AppError> {
+    let data = json!({
+        "artistId": id,
+        "limit": limit,
+        "offset": offset,
+        "total": true,
+    });
+    let response = create_request("/api/artist/mvs", data, option(true))
+        .await?
+        .ensure_success()?;
+
+    Ok(MvPage {
+        mvs: response.parse("/mvs")?,
+        has_more: response.parse("/hasMore").unwrap_or_default(),
+    })
+}
+
+/// 相似歌手，需要登录
+pub async fn get_similar_artists(id: u64) -> Result<Vec<Artist>, AppError> {
+    create_request(
+        "/api/discovery/simiArtist",
+        json!({ "artistid": id }),
+        option(true),
+    )
+    .await?
+    .ensure_success()?
+    .parse("/artists")
+}
+
+/// 关注或取消关注歌手
+pub async fn follow_artist(id: u64, follow: bool) -> Result<(), AppError> {
+    let data = json!({
+        "artistId": id,
+        "artistIds": format!("[{}]", id),
+    });
+    let uri = if follow {
+        "/api/artist/sub"
+    } else {
+        "/api/artist/unsub"
+    };
+
+    create_request(uri, data, option(false))
+        .await?
+        .ensure_success()?;
+    invalidate(&[&artist_uri(id), FOLLOWED_ARTISTS_URI]);
+    Ok(())
+}
+
+/// 已关注的歌手
+pub async fn get_followed_artists(limit: u32, offset: u32) -> Result<Vec<Artist>, AppError> {
+    let data = json!({ "limit": limit, "offset": offset, "total": true });
+    create_request(FOLLOWED_ARTISTS_URI, data, option(true))
+        .await?
+        .ensure_success()?
+        .parse("/data")
+}
@@ -1,6 +1,7 @@
 use crate::get_device_id;
 use crate::models::http::RequestOption;
 use crate::network::request::create_request;
+use crate::storage::cookie::get_cookie_manager;
 use crate::{error::AppError, models::http::Response};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
@@ -16,6 +17,7 @@ pub async fn register_anonimous() -> Result<Response, AppError> {
         headers: None,
         e_r: None,
         cache: Some(false),
+        domain: None,
     };
 
     let device_id = get_device_id();
@@ -31,6 +33,26 @@ pub async fn register_anonimous() -> Result<Response, AppError> {
     create_request("/api/register/anonimous", data, option).await
 }
 
+/// 获取当前登录账号的 uid，未登录时返回 `None`
+pub async fn get_login_uid() -> Result<Option<u64>, AppError> {
+    if !get_cookie_manager().has_login_cookie() {
+        return Ok(None);
+    }
+
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        ..Default::default()
+    };
+    let response = create_request("/api/nuser/account/get", json!({}), option)
+        .await?
+        .ensure_success()?;
+
+    Ok(response
+        .body
+        .pointer("/account/id")
+        .and_then(|v| v.as_u64()))
+}
+
 fn cloud_music_dll_encode_id(some_id: &&str) -> String {
     const ID_XOR_KEY: &[u8] = b"3go8&$8*3*3h0k(2)2";
 
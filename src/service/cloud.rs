@@ -0,0 +1,563 @@
+use log::{debug, info, warn};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use serde_json::{Value, json};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::models::cloud::{CloudPage, CloudSongMeta, UploadSession};
+use crate::models::http::RequestOption;
+use crate::network::request::create_request;
+use crate::storage::upload::get_upload_store;
+
+const BUCKET: &str = "jd-musicrep-privatecloud-audio-public";
+/// NOS 分片大小
+const CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+const DEFAULT_BITRATE: u64 = 999000;
+
+/// 云盘用到的各个服务地址，测试时可以全部指向本地
+#[derive(Debug, Clone)]
+pub struct CloudHosts {
+    /// 网易云接口域名，`None` 使用默认域名
+    pub api: Option<String>,
+    /// NOS 调度服务，用于获取可用的上传节点
+    pub lbs: String,
+    /// 固定的上传节点，设置后不再请求调度服务
+    pub upload: Option<String>,
+}
+
+impl Default for CloudHosts {
+    fn default() -> Self {
+        Self {
+            api: None,
+            lbs: "https://wanproxy.127.net/lbs".to_string(),
+            upload: None,
+        }
+    }
+}
+
+pub struct CloudDrive {
+    hosts: CloudHosts,
+    client: reqwest::Client,
+}
+
+impl Default for CloudDrive {
+    fn default() -> Self {
+        Self::new(CloudHosts::default())
+    }
+}
+
+fn file_md5(path: &Path) -> Result<String, AppError> {
+    let mut file = File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        context.consume(&buf[..n]);
+    }
+    Ok(format!("{:x}", context.finalize()))
+}
+
+fn read_chunk(path: &Path, offset: u64, len: u64) -> Result<Vec<u8>, AppError> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Thread(e.to_string()))?
+}
+
+impl CloudDrive {
+    pub fn new(hosts: CloudHosts) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { hosts, client }
+    }
+
+    fn option(&self, cache: bool) -> RequestOption {
+        RequestOption {
+            crypto: Some("weapi".into()),
+            cache: Some(cache),
+            domain: self.hosts.api.clone(),
+            ..Default::default()
+        }
+    }
+
+    async fn request(&self, uri: &str, data: Value) -> Result<Value, AppError> {
+        Ok(create_request(uri, data, self.option(false))
+            .await?
+            .ensure_success()?
+            .body)
+    }
+
+    pub async fn list(&self, limit: u32, offset: u32) -> Result<CloudPage, AppError> {
+        let data = json!({ "limit": limit, "offset": offset });
+        let response = create_request("/api/v1/cloud/get", data, self.option(false))
+            .await?
+            .ensure_success()?;
+
+        Ok(CloudPage {
+            songs: response.parse("/data")?,
+            count: response.parse("/count").unwrap_or_default(),
+            size: response
+                .parse::<String>("/size")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            max_size: response
+                .parse::<String>("/maxSize")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            has_more: response.parse("/hasMore").unwrap_or_default(),
+        })
+    }
+
+    pub async fn delete(&self, song_ids: &[u64]) -> Result<(), AppError> {
+        self.request("/api/cloud/del", json!({ "songIds": song_ids }))
+            .await?;
+        Ok(())
+    }
+
+    /// 将云盘歌曲关联到官方曲目，`official_id` 为 0 时取消关联
+    pub async fn match_song(
+        &self,
+        uid: u64,
+        cloud_song_id: u64,
+        official_id: u64,
+    ) -> Result<(), AppError> {
+        let data = json!({
+            "userId": uid,
+            "songId": cloud_song_id,
+            "adjustSongId": official_id,
+        });
+        self.request("/api/cloud/user/song/match", data).await?;
+        Ok(())
+    }
+
+    /// 上传本地音频到云盘，返回云盘歌曲ID
+    ///
+    /// 分片进度保存在本地，同一文件中断后再次上传会从断点继续。
+    /// `on_progress` 以 `(已上传字节, 总字节)` 调用
+    pub async fn upload(
+        &self,
+        path: &Path,
+        meta: CloudSongMeta,
+        on_progress: impl Fn(u64, u64),
+    ) -> Result<u64, AppError> {
+        let path = path.to_path_buf();
+        let size = std::fs::metadata(&path)?.len();
+        let md5 = blocking({
+            let path = path.clone();
+            move || file_md5(&path)
+        })
+        .await?;
+
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp3")
+            .to_lowercase();
+        let filename = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let bitrate = meta.bitrate.unwrap_or(DEFAULT_BITRATE);
+
+        let check = self
+            .request(
+                "/api/cloud/upload/check",
+                json!({
+                    "bitrate": bitrate.to_string(),
+                    "ext": "",
+                    "length": size,
+                    "md5": md5,
+                    "songId": "0",
+                    "version": 1,
+                }),
+            )
+            .await?;
+        let need_upload = check["needUpload"].as_bool().unwrap_or(true);
+        let song_id = match &check["songId"] {
+            Value::String(s) => s.clone(),
+            v => v.to_string(),
+        };
+
+        let store = get_upload_store();
+        let mut session = match store.get(&md5) {
+            Some(session) => session,
+            None => self.allocate(&md5, &filename, &ext).await?,
+        };
+
+        if need_upload {
+            self.upload_chunks(&path, &md5, &mut session, size, &on_progress)
+                .await?;
+        } else {
+            debug!("{} already exists in cloud storage, skipping upload", md5);
+            on_progress(size, size);
+        }
+
+        let info = self
+            .request(
+                "/api/upload/cloud/info/v2",
+                json!({
+                    "md5": md5,
+                    "songid": song_id,
+                    "filename": format!("{}.{}", filename, ext),
+                    "song": meta.song.unwrap_or_else(|| filename.clone()),
+                    "album": meta.album.unwrap_or_else(|| "未知专辑".to_string()),
+                    "artist": meta.artist.unwrap_or_else(|| "未知艺术家".to_string()),
+                    "bitrate": bitrate.to_string(),
+                    "resourceId": session.resource_id,
+                }),
+            )
+            .await?;
+        let cloud_id = match &info["songId"] {
+            Value::String(s) => s.parse().ok(),
+            v => v.as_u64(),
+        }
+        .ok_or_else(|| AppError::Format("Missing songId in cloud info response".to_string()))?;
+
+        self.request("/api/cloud/pub/v2", json!({ "songid": cloud_id }))
+            .await?;
+        store.remove(&md5);
+
+        info!("Uploaded {} to cloud as {}", path.display(), cloud_id);
+        Ok(cloud_id)
+    }
+
+    async fn allocate(
+        &self,
+        md5: &str,
+        filename: &str,
+        ext: &str,
+    ) -> Result<UploadSession, AppError> {
+        let body = self
+            .request(
+                "/api/nos/token/alloc",
+                json!({
+                    "bucket": BUCKET,
+                    "ext": ext,
+                    "filename": filename,
+                    "local": false,
+                    "nos_product": 3,
+                    "type": "audio",
+                    "md5": md5,
+                }),
+            )
+            .await?;
+        let result = &body["result"];
+
+        Ok(UploadSession {
+            bucket: result["bucket"].as_str().unwrap_or(BUCKET).to_string(),
+            object_key: result["objectKey"]
+                .as_str()
+                .ok_or_else(|| AppError::Format("Missing objectKey in token".to_string()))?
+                .to_string(),
+            token: result["token"]
+                .as_str()
+                .ok_or_else(|| AppError::Format("Missing token in token".to_string()))?
+                .to_string(),
+            resource_id: result["resourceId"].as_u64().unwrap_or_default(),
+            host: self.upload_host().await?,
+            context: None,
+            offset: 0,
+        })
+    }
+
+    async fn upload_host(&self) -> Result<String, AppError> {
+        if let Some(host) = &self.hosts.upload {
+            return Ok(host.clone());
+        }
+
+        let lbs: Value = self
+            .client
+            .get(&self.hosts.lbs)
+            .query(&[("version", "1.0"), ("bucketname", BUCKET)])
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        lbs["upload"][0]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| AppError::Network("No upload host returned by lbs".to_string()))
+    }
+
+    fn object_url(&self, session: &UploadSession) -> String {
+        format!(
+            "{}/{}/{}",
+            session.host,
+            session.bucket,
+            urlencoding::encode(&session.object_key)
+        )
+    }
+
+    /// 查询服务端已接收的字节数
+    async fn uploaded_offset(
+        &self,
+        session: &UploadSession,
+        context: &str,
+    ) -> Result<u64, AppError> {
+        let body: Value = self
+            .client
+            .get(format!("{}?uploadContext", self.object_url(session)))
+            .query(&[("version", "1.0"), ("context", context)])
+            .header("x-nos-token", &session.token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(body["offset"].as_u64().unwrap_or_default())
+    }
+
+    async fn upload_chunks(
+        &self,
+        path: &Path,
+        md5: &str,
+        session: &mut UploadSession,
+        size: u64,
+        on_progress: &impl Fn(u64, u64),
+    ) -> Result<(), AppError> {
+        if let Some(context) = session.context.clone() {
+            match self.uploaded_offset(session, &context).await {
+                Ok(offset) => {
+                    info!("Resuming upload of {} at {}/{}", md5, offset, size);
+                    session.offset = offset;
+                }
+                // 上下文已失效时从头开始
+                Err(e) => {
+                    debug!("Upload context of {} expired: {}", md5, e);
+                    session.context = None;
+                    session.offset = 0;
+                }
+            }
+        }
+        // 文件被替换或服务端返回异常进度时不能续传
+        if session.offset > size {
+            warn!(
+                "Upload offset {} of {} exceeds file size {}, restarting",
+                session.offset, md5, size
+            );
+            session.context = None;
+            session.offset = 0;
+        }
+        on_progress(session.offset, size);
+
+        loop {
+            let len = CHUNK_SIZE.min(size - session.offset);
+            let chunk = blocking({
+                let path = path.to_path_buf();
+                let offset = session.offset;
+                move || read_chunk(&path, offset, len)
+            })
+            .await?;
+            let complete = session.offset + len >= size;
+
+            let mut query = vec![
+                ("offset", session.offset.to_string()),
+                ("complete", complete.to_string()),
+                ("version", "1.0".to_string()),
+            ];
+            if let Some(context) = &session.context {
+                query.push(("context", context.clone()));
+            }
+
+            let body: Value = self
+                .client
+                .post(self.object_url(session))
+                .query(&query)
+                .header("x-nos-token", &session.token)
+                .header(CONTENT_TYPE, "audio/mpeg")
+                .header(CONTENT_LENGTH, chunk.len())
+                .body(chunk)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            session.context = body["context"]
+                .as_str()
+                .map(|s| s.to_string())
+                .or(session.context.take());
+            session.offset = body["offset"].as_u64().unwrap_or(session.offset + len);
+            get_upload_store().save(md5, session);
+            on_progress(session.offset, size);
+
+            if complete {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockServer, Response};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Nos {
+        /// `?uploadContext` 返回的已上传字节数
+        reported: u64,
+        /// 每个分片的 `(offset, context)`
+        chunks: Vec<(u64, Option<String>)>,
+        data: Vec<u8>,
+    }
+
+    fn mock(nos: Arc<Mutex<Nos>>) -> MockServer {
+        MockServer::start(move |req| {
+            let mut nos = nos.lock().unwrap();
+            match req.path.as_str() {
+                "/weapi/cloud/upload/check" => {
+                    Response::json(json!({ "code": 200, "needUpload": true, "songId": "0" }))
+                }
+                "/weapi/nos/token/alloc" => Response::json(json!({
+                    "code": 200,
+                    "result": { "bucket": "cloud", "objectKey": "obj", "token": "t", "resourceId": 7 },
+                })),
+                "/weapi/upload/cloud/info/v2" => {
+                    Response::json(json!({ "code": 200, "songId": "42" }))
+                }
+                "/weapi/cloud/pub/v2" => Response::json(json!({ "code": 200 })),
+                "/cloud/obj" if req.method == "GET" => {
+                    Response::json(json!({ "offset": nos.reported }))
+                }
+                "/cloud/obj" => {
+                    assert_eq!(req.header("x-nos-token"), Some("t"));
+                    let offset: u64 = req.param("offset").unwrap().parse().unwrap();
+                    nos.chunks.push((offset, req.param("context")));
+                    nos.data.truncate(offset as usize);
+                    nos.data.extend_from_slice(&req.body);
+                    Response::json(json!({ "context": "ctx", "offset": nos.data.len() }))
+                }
+                _ => Response::new(404, ""),
+            }
+        })
+    }
+
+    fn drive(server: &MockServer) -> CloudDrive {
+        CloudDrive::new(CloudHosts {
+            api: Some(server.url().to_string()),
+            lbs: String::new(),
+            upload: Some(server.url().to_string()),
+        })
+    }
+
+    /// 内容各不相同，避免用例之间共用上传记录
+    fn audio_file(name: &str, size: usize) -> (std::path::PathBuf, Vec<u8>) {
+        let data: Vec<u8> = (0..size).map(|i| (i * 31 + name.len()) as u8).collect();
+        let path =
+            std::env::temp_dir().join(format!("cloubit-{}-{}.mp3", name, std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        (path, data)
+    }
+
+    fn session(server: &MockServer, offset: u64) -> UploadSession {
+        UploadSession {
+            bucket: "cloud".into(),
+            object_key: "obj".into(),
+            token: "t".into(),
+            resource_id: 7,
+            host: server.url().to_string(),
+            context: Some("ctx".into()),
+            offset,
+        }
+    }
+
+    #[tokio::test]
+    async fn uploads_in_chunks() {
+        let nos = Arc::new(Mutex::new(Nos::default()));
+        let server = mock(nos.clone());
+        let (path, data) = audio_file("upload", CHUNK_SIZE as usize * 2 + 1000);
+
+        let progress = Mutex::new(Vec::new());
+        let id = drive(&server)
+            .upload(&path, CloudSongMeta::default(), |done, total| {
+                progress.lock().unwrap().push((done, total))
+            })
+            .await
+            .unwrap();
+
+        let nos = nos.lock().unwrap();
+        assert_eq!(id, 42);
+        assert_eq!(nos.data, data);
+        let offsets: Vec<_> = nos.chunks.iter().map(|(offset, _)| *offset).collect();
+        assert_eq!(offsets, [0, CHUNK_SIZE, CHUNK_SIZE * 2]);
+        assert_eq!(nos.chunks[0].1, None);
+        let size = data.len() as u64;
+        assert_eq!(progress.lock().unwrap().last(), Some(&(size, size)));
+
+        let md5 = file_md5(&path).unwrap();
+        assert!(get_upload_store().get(&md5).is_none());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn resumes_from_server_offset() {
+        let nos = Arc::new(Mutex::new(Nos::default()));
+        let server = mock(nos.clone());
+        let (path, data) = audio_file("resume", CHUNK_SIZE as usize + 1000);
+        let md5 = file_md5(&path).unwrap();
+
+        {
+            let mut nos = nos.lock().unwrap();
+            nos.reported = CHUNK_SIZE;
+            nos.data = data[..CHUNK_SIZE as usize].to_vec();
+        }
+        get_upload_store().save(&md5, &session(&server, 0));
+
+        drive(&server)
+            .upload(&path, CloudSongMeta::default(), |_, _| {})
+            .await
+            .unwrap();
+
+        let nos = nos.lock().unwrap();
+        assert_eq!(nos.chunks, [(CHUNK_SIZE, Some("ctx".to_string()))]);
+        assert_eq!(nos.data, data);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn restarts_when_offset_exceeds_size() {
+        let nos = Arc::new(Mutex::new(Nos::default()));
+        let server = mock(nos.clone());
+        let (path, data) = audio_file("stale", 1000);
+        let md5 = file_md5(&path).unwrap();
+
+        nos.lock().unwrap().reported = CHUNK_SIZE;
+        get_upload_store().save(&md5, &session(&server, CHUNK_SIZE));
+
+        drive(&server)
+            .upload(&path, CloudSongMeta::default(), |_, _| {})
+            .await
+            .unwrap();
+
+        let nos = nos.lock().unwrap();
+        assert_eq!(nos.chunks, [(0, None)]);
+        assert_eq!(nos.data, data);
+        let _ = std::fs::remove_file(path);
+    }
+}
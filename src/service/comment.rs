@@ -0,0 +1,179 @@
+use serde_json::{Value, json};
+
+use crate::error::AppError;
+use crate::models::comment::{Comment, CommentPage, CommentPaging};
+use crate::models::http::RequestOption;
+use crate::models::resource::ResourceType;
+use crate::network::request::create_request;
+
+fn weapi_option() -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    }
+}
+
+fn value_to_cursor(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+pub async fn get_comments(
+    resource: ResourceType,
+    id: u64,
+    paging: &CommentPaging,
+) -> Result<CommentPage, AppError> {
+    let option = RequestOption {
+        crypto: Some("eapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    };
+    let data = json!({
+        "threadId": resource.thread_id(id),
+        "pageNo": paging.page_no,
+        "showInner": true,
+        "pageSize": paging.page_size,
+        "cursor": paging.cursor_value(),
+        "sortType": paging.sort.code(),
+    });
+
+    let response = create_request("/api/v2/resource/comments", data, option)
+        .await?
+        .ensure_success()?;
+
+    Ok(CommentPage {
+        comments: response.parse("/data/comments")?,
+        total: response.parse("/data/totalCount").unwrap_or_default(),
+        has_more: response.parse("/data/hasMore").unwrap_or_default(),
+        cursor: value_to_cursor(response.body.pointer("/data/cursor")),
+    })
+}
+
+pub async fn get_hot_comments(
+    resource: ResourceType,
+    id: u64,
+    limit: u32,
+    offset: u32,
+) -> Result<CommentPage, AppError> {
+    let data = json!({
+        "rid": id,
+        "limit": limit,
+        "offset": offset,
+        "beforeTime": 0,
+    });
+    let uri = format!("/api/v1/resource/hotcomments/{}", resource.thread_id(id));
+
+    let response = create_request(&uri, data, weapi_option())
+        .await?
+        .ensure_success()?;
+
+    Ok(CommentPage {
+        comments: response.parse("/hotComments")?,
+        total: response.parse("/total").unwrap_or_default(),
+        has_more: response.parse("/hasMore").unwrap_or_default(),
+        cursor: Some((offset + limit).to_string()),
+    })
+}
+
+/// 获取楼层回复，`time` 为上一页返回的游标，首次请求传 `None`
+pub async fn get_floor_comments(
+    resource: ResourceType,
+    id: u64,
+    parent_comment_id: u64,
+    time: Option<&str>,
+    limit: u32,
+) -> Result<CommentPage, AppError> {
+    let data = json!({
+        "parentCommentId": parent_comment_id,
+        "threadId": resource.thread_id(id),
+        "time": time.unwrap_or("-1"),
+        "limit": limit,
+    });
+
+    let response = create_request("/api/resource/comment/floor/get", data, weapi_option())
+        .await?
+        .ensure_success()?;
+
+    Ok(CommentPage {
+        comments: response.parse("/data/comments")?,
+        total: response.parse("/data/totalCount").unwrap_or_default(),
+        has_more: response.parse("/data/hasMore").unwrap_or_default(),
+        cursor: value_to_cursor(response.body.pointer("/data/time")),
+    })
+}
+
+pub async fn post_comment(
+    resource: ResourceType,
+    id: u64,
+    content: &str,
+) -> Result<Comment, AppError> {
+    let data = json!({
+        "threadId": resource.thread_id(id),
+        "content": content,
+    });
+
+    create_request("/api/resource/comments/add", data, weapi_option())
+        .await?
+        .ensure_success()?
+        .parse("/comment")
+}
+
+pub async fn reply_comment(
+    resource: ResourceType,
+    id: u64,
+    comment_id: u64,
+    content: &str,
+) -> Result<Comment, AppError> {
+    let data = json!({
+        "threadId": resource.thread_id(id),
+        "commentId": comment_id,
+        "content": content,
+    });
+
+    create_request("/api/resource/comments/reply", data, weapi_option())
+        .await?
+        .ensure_success()?
+        .parse("/comment")
+}
+
+pub async fn delete_comment(
+    resource: ResourceType,
+    id: u64,
+    comment_id: u64,
+) -> Result<(), AppError> {
+    let data = json!({
+        "threadId": resource.thread_id(id),
+        "commentId": comment_id,
+    });
+
+    create_request("/api/resource/comments/delete", data, weapi_option())
+        .await?
+        .ensure_success()?;
+    Ok(())
+}
+
+pub async fn like_comment(
+    resource: ResourceType,
+    id: u64,
+    comment_id: u64,
+    like: bool,
+) -> Result<(), AppError> {
+    let data = json!({
+        "threadId": resource.thread_id(id),
+        "commentId": comment_id,
+    });
+    let uri = if like {
+        "/api/v1/comment/like"
+    } else {
+        "/api/v1/comment/unlike"
+    };
+
+    create_request(uri, data, weapi_option())
+        .await?
+        .ensure_success()?;
+    Ok(())
+}
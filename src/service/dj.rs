@@ -0,0 +1,98 @@
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::dj::{DjCategory, DjProgram, DjRadio, ProgramPage, ProgramSort};
+use crate::models::http::RequestOption;
+use crate::network::request::create_request;
+use crate::storage::cache::invalidate;
+
+const RADIO_DETAIL_URI: &str = "/api/djradio/v2/get";
+const SUBSCRIBED_RADIOS_URI: &str = "/api/djradio/get/subed";
+
+fn option(cache: bool) -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(cache),
+        ..Default::default()
+    }
+}
+
+pub async fn get_categories() -> Result<Vec<DjCategory>, AppError> {
+    create_request("/api/djradio/category/get", json!({}), option(true))
+        .await?
+        .ensure_success()?
+        .parse("/categories")
+}
+
+/// 分类下的热门电台
+pub async fn get_category_radios(
+    category_id: u64,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<DjRadio>, AppError> {
+    let data = json!({ "cateId": category_id, "limit": limit, "offset": offset });
+    create_request("/api/djradio/hot", data, option(true))
+        .await?
+        .ensure_success()?
+        .parse("/djRadios")
+}
+
+pub async fn get_radio(id: u64) -> Result<DjRadio, AppError> {
+    create_request(RADIO_DETAIL_URI, json!({ "id": id }), option(true))
+        .await?
+        .ensure_success()?
+        .parse("/data")
+}
+
+pub async fn get_programs(
+    radio_id: u64,
+    sort: ProgramSort,
+    limit: u32,
+    offset: u32,
+) -> Result<ProgramPage, AppError> {
+    let data = json!({
+        "radioId": radio_id,
+        "limit": limit,
+        "offset": offset,
+        "asc": sort.is_asc(),
+    });
+    let response = create_request("/api/dj/program/byradio", data, option(true))
+        .await?
+        .ensure_success()?;
+
+    Ok(ProgramPage {
+        programs: response.parse("/programs")?,
+        has_more: response.parse("/more").unwrap_or_default(),
+    })
+}
+
+pub async fn get_program(id: u64) -> Result<DjProgram, AppError> {
+    create_request("/api/dj/program/detail", json!({ "id": id }), option(true))
+        .await?
+        .ensure_success()?
+        .parse("/program")
+}
+
+/// 订阅或取消订阅电台
+pub async fn subscribe_radio(id: u64, subscribe: bool) -> Result<(), AppError> {
+    let uri = if subscribe {
+        "/api/djradio/sub"
+    } else {
+        "/api/djradio/unsub"
+    };
+
+    create_request(uri, json!({ "id": id }), option(false))
+        .await?
+        .ensure_success()?;
+    invalidate(&[RADIO_DETAIL_URI, SUBSCRIBED_RADIOS_URI]);
+    Ok(())
+}
+
+/// 已订阅的电台
+pub async fn get_subscribed_radios(limit: u32, offset: u32) -> Result<Vec<DjRadio>, AppError> {
+    let data = json!({ "limit": limit, "offset": offset, "total": true });
+    create_request(SUBSCRIBED_RADIOS_URI, data, option(true))
+        .await?
+        .ensure_success()?
+        .parse("/djRadios")
+}
@@ -0,0 +1,141 @@
+use log::{debug, info, warn};
+use serde_json::json;
+use std::time::Duration;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::network::request::create_request;
+use crate::service::auth::get_login_uid;
+use crate::storage::like::get_like_store;
+
+/// 离线操作重放与喜欢列表同步的间隔
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+/// 每隔多少个同步周期从服务端拉取一次完整列表
+const FULL_SYNC_EVERY: u32 = 10;
+
+async fn send_like(id: u64, like: bool) -> Result<(), AppError> {
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    };
+    let data = json!({
+        "alg": "itembased",
+        "trackId": id,
+        "like": like,
+        "time": "3",
+    });
+
+    create_request("/api/radio/like", data, option)
+        .await?
+        .ensure_success()?;
+    Ok(())
+}
+
+pub fn is_liked(id: u64) -> bool {
+    get_like_store().is_liked(id)
+}
+
+/// 喜欢或取消喜欢一首歌，本地状态立即生效
+///
+/// 网络不可用时操作会被记录下来，由 [`replay_pending`] 在网络恢复后重新提交
+pub async fn like_song(id: u64, like: bool) -> Result<(), AppError> {
+    let store = get_like_store();
+    let previous = store.is_liked(id);
+    store.set_liked(id, like);
+
+    match send_like(id, like).await {
+        Ok(()) => {
+            store.clear_pending(id);
+            Ok(())
+        }
+        Err(AppError::Connection(e)) => {
+            warn!("Offline, queued like of {} ({}): {}", id, like, e);
+            store.queue_pending(id, like);
+            Ok(())
+        }
+        Err(e) => {
+            store.set_liked(id, previous);
+            Err(e)
+        }
+    }
+}
+
+/// 重新提交离线期间的操作，返回成功提交的数量
+pub async fn replay_pending() -> Result<usize, AppError> {
+    let store = get_like_store();
+    let mut replayed = 0;
+
+    for (id, like) in store.pending() {
+        match send_like(id, like).await {
+            Ok(()) => {
+                store.clear_pending(id);
+                replayed += 1;
+            }
+            // 仍然离线，等待下一次重放
+            Err(e @ AppError::Connection(_)) => return Err(e),
+            Err(e) => {
+                warn!("Dropping pending like of {} ({}): {}", id, like, e);
+                store.clear_pending(id);
+                store.set_liked(id, !like);
+            }
+        }
+    }
+
+    Ok(replayed)
+}
+
+/// 从服务端拉取喜欢列表并增量更新本地缓存
+pub async fn sync_liked_songs() -> Result<(), AppError> {
+    let Some(uid) = get_login_uid().await? else {
+        debug!("Not logged in, skipping liked songs sync");
+        return Ok(());
+    };
+
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    };
+    let ids: Vec<u64> = create_request("/api/song/like/get", json!({ "uid": uid }), option)
+        .await?
+        .ensure_success()?
+        .parse("/ids")?;
+
+    let (added, removed) = get_like_store().apply_remote(&ids);
+    info!(
+        "Liked songs synced: {} total, +{} -{}",
+        get_like_store().len(),
+        added,
+        removed
+    );
+    Ok(())
+}
+
+/// 启动后台任务：定期重放离线操作并同步喜欢列表，每次同步后调用 `on_synced`
+pub fn spawn_sync_task(runtime: &tokio::runtime::Handle, on_synced: impl Fn() + Send + 'static) {
+    runtime.spawn(async move {
+        let mut interval = tokio::time::interval(SYNC_INTERVAL);
+        let mut tick: u32 = 0;
+
+        loop {
+            interval.tick().await;
+
+            if !get_like_store().pending().is_empty() {
+                match replay_pending().await {
+                    Ok(count) if count > 0 => info!("Replayed {} offline like(s)", count),
+                    Ok(_) => {}
+                    Err(e) => debug!("Pending likes not replayed yet: {}", e),
+                }
+            }
+
+            if tick == 0 {
+                match sync_liked_songs().await {
+                    Ok(()) => on_synced(),
+                    Err(e) => warn!("Failed to sync liked songs: {}", e),
+                }
+            }
+            tick = (tick + 1) % FULL_SYNC_EVERY;
+        }
+    });
+}
@@ -1 +1,16 @@
+pub mod album;
+pub mod artist;
 pub mod auth;
+pub mod cloud;
+pub mod comment;
+pub mod dj;
+pub mod like;
+pub mod playlist;
+pub mod recommend;
+pub mod scrobble;
+pub mod search;
+pub mod similar;
+pub mod song;
+pub mod toplist;
+pub mod user;
+pub mod video;
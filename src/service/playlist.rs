@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::debug;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::task::JoinSet;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::models::playlist::{Playlist, PlaylistPrivacy};
+use crate::models::song::{Song, TrackId};
+use crate::network::request::create_request;
+use crate::service::song::{SONG_DETAIL_BATCH, get_song_details};
+use crate::storage::cache::invalidate;
+
+const USER_PLAYLIST_URI: &str = "/api/user/playlist";
+const PLAYLIST_DETAIL_URI: &str = "/api/v6/playlist/detail";
+
+/// 网易云限制每个歌单最多 3 个标签
+const MAX_TAGS: usize = 3;
+/// 补齐歌单曲目时同时进行的 song/detail 请求数
+const DETAIL_CONCURRENCY: usize = 4;
+
+fn write_option() -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    }
+}
+
+/// 执行写操作，成功后清除歌单相关的读取缓存
+async fn manipulate(uri: &str, data: Value) -> Result<Value, AppError> {
+    let response = create_request(uri, data, write_option())
+        .await?
+        .ensure_success()?;
+    invalidate(&[USER_PLAYLIST_URI, PLAYLIST_DETAIL_URI]);
+    Ok(response.body)
+}
+
+fn id_list(ids: &[u64]) -> String {
+    format!(
+        "[{}]",
+        ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+/// 获取用户创建与收藏的歌单
+pub async fn get_user_playlists(
+    uid: u64,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<Playlist>, AppError> {
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        ..Default::default()
+    };
+    let data = json!({
+        "uid": uid,
+        "limit": limit,
+        "offset": offset,
+        "includeVideo": true,
+    });
+
+    create_request(USER_PLAYLIST_URI, data, option)
+        .await?
+        .ensure_success()?
+        .parse("/playlist")
+}
+
+pub async fn create_playlist(name: &str, privacy: PlaylistPrivacy) -> Result<Playlist, AppError> {
+    let data = json!({
+        "name": name,
+        "privacy": privacy.code(),
+        "type": "NORMAL",
+    });
+
+    let body = manipulate("/api/playlist/create", data).await?;
+    Ok(Playlist::deserialize(&body["playlist"])?)
+}
+
+pub async fn rename_playlist(id: u64, name: &str) -> Result<(), AppError> {
+    let data = json!({ "id": id, "name": name });
+    manipulate("/api/playlist/update/name", data).await?;
+    Ok(())
+}
+
+pub async fn update_description(id: u64, description: &str) -> Result<(), AppError> {
+    let data = json!({ "id": id, "desc": description });
+    manipulate("/api/playlist/desc/update", data).await?;
+    Ok(())
+}
+
+pub async fn update_tags(id: u64, tags: &[&str]) -> Result<(), AppError> {
+    if tags.len() > MAX_TAGS {
+        return Err(AppError::Common(format!(
+            "A playlist can have at most {} tags",
+            MAX_TAGS
+        )));
+    }
+
+    let data = json!({ "id": id, "tags": tags.join(";") });
+    manipulate("/api/playlist/tags/update", data).await?;
+    Ok(())
+}
+
+pub async fn delete_playlists(ids: &[u64]) -> Result<(), AppError> {
+    let data = json!({ "ids": id_list(ids) });
+    manipulate("/api/playlist/remove", data).await?;
+    Ok(())
+}
+
+pub async fn add_tracks(playlist_id: u64, track_ids: &[u64]) -> Result<(), AppError> {
+    manipulate_tracks("add", playlist_id, track_ids).await
+}
+
+pub async fn remove_tracks(playlist_id: u64, track_ids: &[u64]) -> Result<(), AppError> {
+    manipulate_tracks("del", playlist_id, track_ids).await
+}
+
+/// 按给定顺序重排歌单内的歌曲，`track_ids` 需包含歌单的全部歌曲
+pub async fn reorder_tracks(playlist_id: u64, track_ids: &[u64]) -> Result<(), AppError> {
+    manipulate_tracks("update", playlist_id, track_ids).await
+}
+
+async fn manipulate_tracks(op: &str, playlist_id: u64, track_ids: &[u64]) -> Result<(), AppError> {
+    let data = json!({
+        "op": op,
+        "pid": playlist_id,
+        "trackIds": id_list(track_ids),
+        "imme": "true",
+    });
+    manipulate("/api/playlist/manipulate/tracks", data).await?;
+    Ok(())
+}
+
+/// 调整歌单在用户歌单列表中的顺序
+pub async fn reorder_playlists(playlist_ids: &[u64]) -> Result<(), AppError> {
+    let data = json!({ "ids": id_list(playlist_ids) });
+    manipulate("/api/playlist/order/update", data).await?;
+    Ok(())
+}
+
+pub async fn subscribe_playlist(id: u64, subscribe: bool) -> Result<(), AppError> {
+    let uri = if subscribe {
+        "/api/playlist/subscribe"
+    } else {
+        "/api/playlist/unsubscribe"
+    };
+    manipulate(uri, json!({ "id": id })).await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct PlaylistDetail {
+    pub playlist: Playlist,
+    pub tracks: Vec<Song>,
+}
+
+/// 加载完整歌单
+///
+/// 歌单详情接口会返回全部 `trackIds`，但只附带第一批歌曲的详情，
+/// 剩余部分需要分批通过 song/detail 补齐
+#[derive(Clone, Default)]
+pub struct PlaylistLoader {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PlaylistLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    fn check_cancelled(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            Err(AppError::Common("Playlist loading cancelled".to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// `on_progress` 在每批歌曲加载完成后以 `(已加载, 总数)` 调用
+    pub async fn load(
+        &self,
+        id: u64,
+        on_progress: impl Fn(usize, usize),
+    ) -> Result<PlaylistDetail, AppError> {
+        let option = RequestOption {
+            crypto: Some("weapi".into()),
+            ..Default::default()
+        };
+        let data = json!({ "id": id, "n": 100000, "s": 8 });
+
+        let response = create_request(PLAYLIST_DETAIL_URI, data, option)
+            .await?
+            .ensure_success()?;
+        let playlist: Playlist = response.parse("/playlist")?;
+        let track_ids: Vec<u64> = response
+            .parse::<Vec<TrackId>>("/playlist/trackIds")?
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        let first_batch: Vec<Song> = response.parse("/playlist/tracks").unwrap_or_default();
+
+        let total = track_ids.len();
+        let mut songs: HashMap<u64, Song> = first_batch.into_iter().map(|s| (s.id, s)).collect();
+        on_progress(songs.len().min(total), total);
+
+        let missing: Vec<u64> = track_ids
+            .iter()
+            .copied()
+            .filter(|id| !songs.contains_key(id))
+            .collect();
+        debug!(
+            "Playlist {}: {} tracks, {} need song/detail",
+            id,
+            total,
+            missing.len()
+        );
+
+        let batches: Vec<Vec<u64>> = missing
+            .chunks(SONG_DETAIL_BATCH)
+            .map(|c| c.to_vec())
+            .collect();
+        let mut loaded = songs.len().min(total);
+
+        for group in batches.chunks(DETAIL_CONCURRENCY) {
+            self.check_cancelled()?;
+
+            // JoinSet 释放时会中止未完成的请求，出错或取消时不会留下后台任务
+            let mut requests = JoinSet::new();
+            for batch in group {
+                let batch = batch.clone();
+                requests.spawn(async move { (batch.len(), get_song_details(&batch).await) });
+            }
+
+            while let Some(joined) = requests.join_next().await {
+                self.check_cancelled()?;
+
+                let (count, result) = joined.map_err(|e| AppError::Thread(e.to_string()))?;
+                loaded += count;
+                songs.extend(result?.into_iter().map(|s| (s.id, s)));
+                on_progress(loaded.min(total), total);
+            }
+        }
+        self.check_cancelled()?;
+
+        let tracks = track_ids.iter().filter_map(|id| songs.remove(id)).collect();
+
+        Ok(PlaylistDetail { playlist, tracks })
+    }
+}
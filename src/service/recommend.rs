@@ -0,0 +1,76 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::models::song::Song;
+use crate::network::request::create_request;
+
+fn option(cache: bool) -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(cache),
+        ..Default::default()
+    }
+}
+
+/// 每日推荐歌曲，需要登录
+pub async fn get_daily_songs() -> Result<Vec<Song>, AppError> {
+    create_request("/api/v3/discovery/recommend/songs", json!({}), option(true))
+        .await?
+        .ensure_success()?
+        .parse("/data/dailySongs")
+}
+
+/// 私人FM，每次调用返回新的一批歌曲
+pub async fn get_personal_fm() -> Result<Vec<Song>, AppError> {
+    create_request("/api/v1/radio/get", json!({}), option(false))
+        .await?
+        .ensure_success()?
+        .parse("/data")
+}
+
+/// 将歌曲从私人FM中移除（不再推荐）
+pub async fn fm_trash(id: u64) -> Result<(), AppError> {
+    let data = json!({
+        "songId": id,
+        "alg": "RT",
+        "time": 25,
+    });
+
+    create_request("/api/radio/trash/add", data, option(false))
+        .await?
+        .ensure_success()?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntelligenceItem {
+    song_info: Song,
+}
+
+/// 心动模式，以 `song_id` 为种子、在 `playlist_id` 歌单中生成推荐
+///
+/// `start_id` 为当前播放的歌曲，续播时传入上一批的最后一首
+pub async fn get_intelligence_list(
+    song_id: u64,
+    playlist_id: u64,
+    start_id: u64,
+) -> Result<Vec<Song>, AppError> {
+    let data = json!({
+        "songId": song_id,
+        "type": "fromPlayOne",
+        "playlistId": playlist_id,
+        "startMusicId": start_id,
+        "count": 1,
+    });
+
+    let items: Vec<IntelligenceItem> =
+        create_request("/api/playmode/intelligence/list", data, option(false))
+            .await?
+            .ensure_success()?
+            .parse("/data")?;
+
+    Ok(items.into_iter().map(|item| item.song_info).collect())
+}
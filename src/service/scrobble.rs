@@ -0,0 +1,154 @@
+use log::{debug, warn};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::error::AppError;
+use crate::models::audio::{EndReason, PlayOrigin, PlayerEvent};
+use crate::models::http::RequestOption;
+use crate::network::request::create_request;
+use crate::storage::scrobble::get_scrobble_queue;
+
+/// 单次 weblog 请求最多携带的记录数
+const BATCH_SIZE: usize = 50;
+/// 离线队列的重试间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(300);
+/// 短于该时长的播放不计入听歌记录
+const MIN_LISTENED: Duration = Duration::from_secs(5);
+
+fn end_name(reason: EndReason) -> &'static str {
+    match reason {
+        EndReason::Finished => "playend",
+        EndReason::Skipped => "ui",
+        EndReason::Interrupted => "interrupt",
+    }
+}
+
+fn start_log(id: u64, origin: &PlayOrigin) -> Value {
+    json!({
+        "action": "startplay",
+        "json": {
+            "id": id.to_string(),
+            "type": "song",
+            "source": origin.source_name(),
+            "sourceId": origin.source_id(),
+            "content": "",
+            "mainsite": "1",
+        }
+    })
+}
+
+fn play_log(id: u64, origin: &PlayOrigin, listened: Duration, reason: EndReason) -> Value {
+    json!({
+        "action": "play",
+        "json": {
+            "download": 0,
+            "end": end_name(reason),
+            "id": id,
+            "sourceId": origin.source_id(),
+            "time": listened.as_secs(),
+            "type": "song",
+            "wifi": 0,
+            "source": origin.source_name(),
+            "mainsite": 1,
+            "content": "",
+        }
+    })
+}
+
+async fn send_logs(logs: &[Value]) -> Result<(), AppError> {
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    };
+    let data = json!({ "logs": serde_json::to_string(logs)? });
+
+    create_request("/api/feedback/weblog", data, option)
+        .await?
+        .ensure_success()?;
+    Ok(())
+}
+
+/// 上报一条记录，网络不可用时放入离线队列
+async fn report(log: Value) {
+    match send_logs(std::slice::from_ref(&log)).await {
+        Ok(()) => {
+            if !get_scrobble_queue().is_empty() {
+                if let Err(e) = flush().await {
+                    debug!("Scrobble queue not flushed: {}", e);
+                }
+            }
+        }
+        Err(AppError::Connection(e)) => {
+            debug!("Offline, queued scrobble: {}", e);
+            get_scrobble_queue().push(&log);
+        }
+        Err(e) => warn!("Failed to scrobble: {}", e),
+    }
+}
+
+/// 上报离线队列中的全部记录
+pub async fn flush() -> Result<(), AppError> {
+    let queue = get_scrobble_queue();
+    loop {
+        let batch = queue.peek(BATCH_SIZE);
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let (keys, logs): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+        match send_logs(&logs).await {
+            Ok(()) => queue.remove(&keys),
+            Err(e @ AppError::Connection(_)) => return Err(e),
+            // 服务端拒绝的记录重试也没有意义
+            Err(e) => {
+                warn!("Dropping {} queued scrobble(s): {}", keys.len(), e);
+                queue.remove(&keys);
+            }
+        }
+    }
+}
+
+fn event_to_log(event: PlayerEvent) -> Option<Value> {
+    match event {
+        PlayerEvent::TrackStarted { song, origin } => Some(start_log(song.id, &origin)),
+        PlayerEvent::TrackEnded {
+            song,
+            origin,
+            listened,
+            reason,
+        } => (listened >= MIN_LISTENED).then(|| play_log(song.id, &origin, listened, reason)),
+        _ => None,
+    }
+}
+
+/// 根据播放事件上报听歌记录，并定期重试离线队列
+pub fn spawn(runtime: Handle, mut events: broadcast::Receiver<PlayerEvent>) {
+    runtime.spawn(async {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = flush().await {
+                debug!("Scrobble queue not flushed: {}", e);
+            }
+        }
+    });
+
+    runtime.spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if let Some(log) = event_to_log(event) {
+                        tokio::spawn(report(log));
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Scrobbler lagged behind, {} event(s) dropped", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
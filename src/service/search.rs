@@ -0,0 +1,46 @@
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::models::search::{DefaultKeyword, HotSearch, SearchSuggest};
+use crate::network::request::create_request;
+
+/// 搜索建议，同时匹配歌曲、歌手、专辑与歌单
+pub async fn get_suggestions(keyword: &str) -> Result<SearchSuggest, AppError> {
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        ..Default::default()
+    };
+
+    let response = create_request("/api/search/suggest/web", json!({ "s": keyword }), option)
+        .await?
+        .ensure_success()?;
+    // 没有任何匹配时不返回 result
+    Ok(response.parse("/result").unwrap_or_default())
+}
+
+/// 热搜榜
+pub async fn get_hot_searches() -> Result<Vec<HotSearch>, AppError> {
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        ..Default::default()
+    };
+
+    create_request("/api/hotsearchlist/get", json!({}), option)
+        .await?
+        .ensure_success()?
+        .parse("/data")
+}
+
+pub async fn get_default_keyword() -> Result<DefaultKeyword, AppError> {
+    let option = RequestOption {
+        crypto: Some("eapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    };
+
+    create_request("/api/search/defaultkeyword/get", json!({}), option)
+        .await?
+        .ensure_success()?
+        .parse("/data")
+}
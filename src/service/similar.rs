@@ -0,0 +1,63 @@
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::artist::Artist;
+use crate::models::http::RequestOption;
+use crate::models::playlist::Playlist;
+use crate::models::song::Song;
+use crate::models::user::UserProfile;
+use crate::network::request::create_request;
+use crate::service::artist;
+
+fn option() -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        ..Default::default()
+    }
+}
+
+pub async fn get_similar_songs(
+    song_id: u64,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<Song>, AppError> {
+    let data = json!({ "songid": song_id, "limit": limit, "offset": offset });
+    create_request("/api/v1/discovery/simiSong", data, option())
+        .await?
+        .ensure_success()?
+        .parse("/songs")
+}
+
+/// 包含这首歌的相似歌单
+pub async fn get_similar_playlists(
+    song_id: u64,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<Playlist>, AppError> {
+    let data = json!({ "songid": song_id, "limit": limit, "offset": offset });
+    create_request("/api/discovery/simiPlaylist", data, option())
+        .await?
+        .ensure_success()?
+        .parse("/playlists")
+}
+
+/// 与这首歌的歌手相似的歌手
+pub async fn get_similar_artists(song: &Song) -> Result<Vec<Artist>, AppError> {
+    match song.artists.first() {
+        Some(first) => artist::get_similar_artists(first.id).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 最近听过这首歌的用户
+pub async fn get_listeners(
+    song_id: u64,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<UserProfile>, AppError> {
+    let data = json!({ "songid": song_id, "limit": limit, "offset": offset });
+    create_request("/api/discovery/simiUser", data, option())
+        .await?
+        .ensure_success()?
+        .parse("/userprofiles")
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::models::song::{Song, SongUrl, SoundQuality};
+use crate::network::request::create_request;
+
+/// song/detail 单次请求的歌曲数量上限
+pub const SONG_DETAIL_BATCH: usize = 500;
+
+/// 批量获取歌曲详情，返回顺序与 `ids` 一致，已下架的歌曲会被跳过
+pub async fn get_song_details(ids: &[u64]) -> Result<Vec<Song>, AppError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        ..Default::default()
+    };
+    let c = ids.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>();
+    let data = json!({ "c": serde_json::to_string(&c)? });
+
+    let songs: Vec<Song> = create_request("/api/v3/song/detail", data, option)
+        .await?
+        .ensure_success()?
+        .parse("/songs")?;
+
+    let mut by_id: HashMap<u64, Song> = songs.into_iter().map(|s| (s.id, s)).collect();
+    Ok(ids.iter().filter_map(|id| by_id.remove(id)).collect())
+}
+
+/// 获取歌曲播放地址，地址有时效性，应在播放前获取
+pub async fn get_song_url(id: u64, quality: SoundQuality) -> Result<SongUrl, AppError> {
+    let option = RequestOption {
+        crypto: Some("eapi".into()),
+        cache: Some(false),
+        ..Default::default()
+    };
+    let data = json!({
+        "ids": format!("[{}]", id),
+        "level": quality.level(),
+        "encodeType": "flac",
+    });
+
+    let mut urls: Vec<SongUrl> = create_request("/api/song/enhance/player/url/v1", data, option)
+        .await?
+        .ensure_success()?
+        .parse("/data")?;
+
+    urls.pop()
+        .ok_or_else(|| AppError::Format(format!("No url returned for song {}", id)))
+}
@@ -0,0 +1,53 @@
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::models::toplist::{ChartDetail, ChartSnapshot, Toplist, Toplists};
+use crate::network::request::create_request;
+use crate::service::playlist::PlaylistLoader;
+use crate::storage::chart::get_chart_store;
+
+/// 获取全部官方榜与全球榜
+pub async fn get_toplists() -> Result<Toplists, AppError> {
+    let option = RequestOption {
+        crypto: Some("weapi".into()),
+        ..Default::default()
+    };
+
+    let (official, global) = create_request("/api/toplist/detail", json!({}), option)
+        .await?
+        .ensure_success()?
+        .parse::<Vec<Toplist>>("/list")?
+        .into_iter()
+        .partition(Toplist::is_official);
+
+    Ok(Toplists { official, global })
+}
+
+/// 榜单在上次查看后是否有更新，不需要加载曲目
+pub fn has_update(toplist: &Toplist) -> bool {
+    get_chart_store().has_update(toplist.id, toplist.track_update_time)
+}
+
+/// 加载榜单全部曲目，并与上次查看时对比
+///
+/// 曲目通过 [`PlaylistLoader`] 加载，可由调用方持有的 `loader` 取消
+pub async fn load_chart(
+    loader: &PlaylistLoader,
+    toplist: Toplist,
+    on_progress: impl Fn(usize, usize),
+) -> Result<ChartDetail, AppError> {
+    let detail = loader.load(toplist.id, on_progress).await?;
+
+    let snapshot = ChartSnapshot {
+        update_time: toplist.track_update_time,
+        track_ids: detail.tracks.iter().map(|s| s.id).collect(),
+    };
+    let diff = get_chart_store().view(toplist.id, snapshot);
+
+    Ok(ChartDetail {
+        toplist,
+        tracks: detail.tracks,
+        diff,
+    })
+}
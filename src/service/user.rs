@@ -0,0 +1,196 @@
+use log::info;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use serde_json::{Value, json};
+use std::path::Path;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::models::playlist::Playlist;
+use crate::models::user::{ProfileUpdate, UserDetail, UserProfile};
+use crate::network::request::create_request;
+use crate::service::playlist::get_user_playlists;
+use crate::storage::cache::invalidate;
+
+const AVATAR_BUCKET: &str = "yyimgs";
+const AVATAR_UPLOAD_HOST: &str = "https://nosup-hz1.127.net";
+/// 分页拉取用户歌单时每页的数量
+const PLAYLIST_PAGE: u32 = 100;
+
+fn option(cache: bool) -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(cache),
+        ..Default::default()
+    }
+}
+
+fn detail_uri(uid: u64) -> String {
+    format!("/api/v1/user/detail/{}", uid)
+}
+
+/// 获取用户主页信息，包括等级、听歌数以及创建和收藏的歌单
+pub async fn get_user_detail(uid: u64) -> Result<UserDetail, AppError> {
+    let response = create_request(&detail_uri(uid), json!({}), option(true))
+        .await?
+        .ensure_success()?;
+
+    let (created_playlists, subscribed_playlists) = get_all_playlists(uid)
+        .await?
+        .into_iter()
+        .partition(|p| p.user_id == uid);
+
+    Ok(UserDetail {
+        profile: response.parse("/profile")?,
+        level: response.parse("/level").unwrap_or_default(),
+        listen_songs: response.parse("/listenSongs").unwrap_or_default(),
+        created_playlists,
+        subscribed_playlists,
+    })
+}
+
+async fn get_all_playlists(uid: u64) -> Result<Vec<Playlist>, AppError> {
+    let mut playlists = Vec::new();
+    loop {
+        let page = get_user_playlists(uid, PLAYLIST_PAGE, playlists.len() as u32).await?;
+        let done = page.len() < PLAYLIST_PAGE as usize;
+        playlists.extend(page);
+        if done {
+            return Ok(playlists);
+        }
+    }
+}
+
+/// 修改当前账号的资料，接口要求提交完整资料，未修改的字段取自 `current`
+pub async fn update_profile(current: &UserProfile, update: ProfileUpdate) -> Result<(), AppError> {
+    let data = json!({
+        "avatarImgId": "0",
+        "nickname": update.nickname.unwrap_or_else(|| current.nickname.clone()),
+        "signature": update
+            .signature
+            .or_else(|| current.signature.clone())
+            .unwrap_or_default(),
+        "gender": update.gender.unwrap_or_else(|| current.gender()).code(),
+        "birthday": update.birthday.unwrap_or(current.birthday),
+        "province": update.province.unwrap_or(current.province),
+        "city": update.city.unwrap_or(current.city),
+    });
+
+    create_request("/api/user/profile/update", data, option(false))
+        .await?
+        .ensure_success()?;
+    invalidate(&[&detail_uri(current.user_id)]);
+    Ok(())
+}
+
+/// 按文件头识别图片格式，返回扩展名和 MIME 类型
+fn image_type(data: &[u8]) -> Option<(&'static str, &'static str)> {
+    match data {
+        [0xFF, 0xD8, 0xFF, ..] => Some(("jpg", "image/jpeg")),
+        [0x89, b'P', b'N', b'G', ..] => Some(("png", "image/png")),
+        [b'G', b'I', b'F', b'8', ..] => Some(("gif", "image/gif")),
+        [
+            b'R',
+            b'I',
+            b'F',
+            b'F',
+            _,
+            _,
+            _,
+            _,
+            b'W',
+            b'E',
+            b'B',
+            b'P',
+            ..,
+        ] => Some(("webp", "image/webp")),
+        _ => None,
+    }
+}
+
+/// 上传图片并设为当前账号的头像
+pub async fn upload_avatar(uid: u64, path: &Path) -> Result<(), AppError> {
+    let data = tokio::fs::read(path).await?;
+    let (ext, mime) = image_type(&data)
+        .ok_or_else(|| AppError::Format(format!("Unsupported avatar image: {}", path.display())))?;
+    let filename = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(|stem| format!("{}.{}", stem, ext))
+        .unwrap_or_else(|| format!("avatar.{}", ext));
+
+    let token = create_request(
+        "/api/nos/token/alloc",
+        json!({
+            "bucket": AVATAR_BUCKET,
+            "ext": ext,
+            "filename": filename,
+            "local": false,
+            "nos_product": 0,
+            "return_body": r#"{"code":200,"size":"$(ObjectSize)"}"#,
+            "type": "other",
+        }),
+        option(false),
+    )
+    .await?
+    .ensure_success()?;
+    let result = &token.body["result"];
+    let (Some(object_key), Some(nos_token)) =
+        (result["objectKey"].as_str(), result["token"].as_str())
+    else {
+        return Err(AppError::Format(
+            "Missing objectKey or token in token".to_string(),
+        ));
+    };
+    let doc_id = match &result["docId"] {
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    };
+
+    reqwest::Client::new()
+        .post(format!(
+            "{}/{}/{}",
+            AVATAR_UPLOAD_HOST,
+            AVATAR_BUCKET,
+            urlencoding::encode(object_key)
+        ))
+        .query(&[("offset", "0"), ("complete", "true"), ("version", "1.0")])
+        .header("x-nos-token", nos_token)
+        .header(CONTENT_TYPE, mime)
+        .header(CONTENT_LENGTH, data.len())
+        .body(data)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    create_request(
+        "/api/user/avatar/upload/v1",
+        json!({ "imgid": doc_id }),
+        option(false),
+    )
+    .await?
+    .ensure_success()?;
+    invalidate(&[&detail_uri(uid)]);
+
+    info!("Updated avatar from {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image_type;
+
+    #[test]
+    fn detects_image_type_from_content() {
+        assert_eq!(
+            image_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(("jpg", "image/jpeg"))
+        );
+        assert_eq!(image_type(b"\x89PNG\r\n\x1a\n"), Some(("png", "image/png")));
+        assert_eq!(image_type(b"GIF89a"), Some(("gif", "image/gif")));
+        assert_eq!(
+            image_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some(("webp", "image/webp"))
+        );
+        assert_eq!(image_type(b"not an image"), None);
+    }
+}
@@ -0,0 +1,135 @@
+use log::{debug, info};
+use reqwest::header::{REFERER, USER_AGENT};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::AppError;
+use crate::models::http::RequestOption;
+use crate::models::video::{MvDetail, Resolution, VideoDetail, VideoStream};
+use crate::network::request::create_request;
+
+/// 视频 CDN 会校验来源，外部播放器和下载都需要带上
+pub const VIDEO_REFERER: &str = "https://music.163.com/";
+pub const VIDEO_USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+
+fn option(cache: bool) -> RequestOption {
+    RequestOption {
+        crypto: Some("weapi".into()),
+        cache: Some(cache),
+        ..Default::default()
+    }
+}
+
+fn stream_from(title: String, value: &Value) -> Result<VideoStream, AppError> {
+    let url = value["url"]
+        .as_str()
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| AppError::Network(format!("No video url available for {}", title)))?;
+
+    Ok(VideoStream {
+        url: url.to_string(),
+        resolution: value["r"].as_u64().unwrap_or_default() as u32,
+        size: value["size"].as_u64().unwrap_or_default(),
+        title,
+    })
+}
+
+pub async fn get_mv_detail(id: u64) -> Result<MvDetail, AppError> {
+    create_request("/api/v1/mv/detail", json!({ "id": id }), option(true))
+        .await?
+        .ensure_success()?
+        .parse("/data")
+}
+
+/// 获取 MV 播放地址，地址有时效性，应在播放前获取
+pub async fn get_mv_stream(mv: &MvDetail, resolution: Resolution) -> Result<VideoStream, AppError> {
+    let data = json!({ "id": mv.id, "r": resolution.value() });
+    let response = create_request("/api/song/enhance/play/mv/url", data, option(false))
+        .await?
+        .ensure_success()?;
+
+    stream_from(
+        format!("{} - {}", mv.name, mv.artist_name),
+        &response.body["data"],
+    )
+}
+
+pub async fn get_video_detail(id: &str) -> Result<VideoDetail, AppError> {
+    create_request(
+        "/api/cloudvideo/v1/video/detail",
+        json!({ "id": id }),
+        option(true),
+    )
+    .await?
+    .ensure_success()?
+    .parse("/data")
+}
+
+pub async fn get_video_stream(
+    video: &VideoDetail,
+    resolution: Resolution,
+) -> Result<VideoStream, AppError> {
+    let data = json!({
+        "ids": format!("[\"{}\"]", video.vid),
+        "resolution": resolution.value(),
+    });
+    let response = create_request("/api/cloudvideo/playurl", data, option(false))
+        .await?
+        .ensure_success()?;
+
+    stream_from(video.title.clone(), &response.body["urls"][0])
+}
+
+/// 下载视频到本地，`on_progress` 以 `(已下载字节, 总字节)` 调用
+///
+/// 先写入同目录的 `.part` 文件，完成后再重命名，失败时不会留下不完整的视频
+pub async fn download(
+    stream: &VideoStream,
+    path: &Path,
+    on_progress: impl Fn(u64, u64),
+) -> Result<(), AppError> {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+
+    let result = match download_to(stream, &part, on_progress).await {
+        Ok(()) => fs::rename(&part, path).await.map_err(AppError::from),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        if let Err(e) = fs::remove_file(&part).await {
+            debug!("Failed to remove {}: {}", part.display(), e);
+        }
+        return Err(e);
+    }
+
+    info!("Downloaded {} to {}", stream.title, path.display());
+    Ok(())
+}
+
+async fn download_to(
+    stream: &VideoStream,
+    path: &Path,
+    on_progress: impl Fn(u64, u64),
+) -> Result<(), AppError> {
+    let mut response = reqwest::Client::new()
+        .get(&stream.url)
+        .header(REFERER, VIDEO_REFERER)
+        .header(USER_AGENT, VIDEO_USER_AGENT)
+        .send()
+        .await?
+        .error_for_status()?;
+    let total = response.content_length().unwrap_or(stream.size);
+
+    let mut file = File::create(path).await?;
+    let mut downloaded = 0;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total);
+    }
+    file.flush().await?;
+    Ok(())
+}
@@ -3,7 +3,8 @@ use std::{
     sync::{Arc, Mutex, OnceLock},
 };
 
-use cached::TimedCache;
+use cached::{Cached, TimedCache};
+use log::debug;
 use serde_json::Value;
 
 use crate::models::http::Response;
@@ -24,5 +25,26 @@ pub fn generate_cache_key(uri: &str, data: &Value) -> String {
     if let Ok(json_str) = serde_json::to_string(data) {
         json_str.hash(&mut hasher);
     }
-    format!("{:x}", hasher.finish())
+    // 保留接口路径作为前缀，便于按接口清除缓存
+    format!("{}#{:x}", uri, hasher.finish())
+}
+
+/// 清除指定接口的全部缓存，用于写操作之后让读取接口拿到最新数据
+pub fn invalidate(uris: &[&str]) {
+    if let Ok(mut cache) = get_cache().lock() {
+        let keys: Vec<String> = cache
+            .get_store()
+            .keys()
+            .filter(|key| {
+                key.rsplit_once('#')
+                    .is_some_and(|(uri, _)| uris.contains(&uri))
+            })
+            .cloned()
+            .collect();
+
+        for key in &keys {
+            cache.cache_remove(key);
+        }
+        debug!("Invalidated {} cache entries for {:?}", keys.len(), uris);
+    }
 }
@@ -0,0 +1,82 @@
+use log::warn;
+use sled::{Db, Tree};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+use crate::models::toplist::{ChartDiff, ChartSnapshot};
+
+/// 每个榜单上次查看时的曲目快照，以榜单ID为键
+#[derive(Clone)]
+pub struct ChartStore {
+    tree: Tree,
+}
+
+static CHART_STORE: OnceLock<ChartStore> = OnceLock::new();
+
+impl ChartStore {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("chart_snapshots")?,
+        })
+    }
+
+    pub fn get(&self, id: u64) -> Option<ChartSnapshot> {
+        self.tree
+            .get(id.to_be_bytes())
+            .ok()
+            .flatten()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+    }
+
+    /// 榜单在上次查看后是否有更新，从未查看过的榜单不算
+    pub fn has_update(&self, id: u64, update_time: u64) -> bool {
+        self.get(id)
+            .is_some_and(|snapshot| snapshot.update_time < update_time)
+    }
+
+    /// 与上次查看的快照对比，并记录本次查看的曲目
+    pub fn view(&self, id: u64, snapshot: ChartSnapshot) -> ChartDiff {
+        let diff = match self.get(id) {
+            Some(previous) => diff(&previous.track_ids, &snapshot.track_ids),
+            None => ChartDiff {
+                first_view: true,
+                ..Default::default()
+            },
+        };
+
+        let result = serde_json::to_vec(&snapshot)
+            .map_err(AppError::from)
+            .and_then(|data| Ok(self.tree.insert(id.to_be_bytes(), data)?));
+        if let Err(e) = result {
+            warn!("Failed to save snapshot of chart {}: {}", id, e);
+        }
+        diff
+    }
+}
+
+fn diff(previous: &[u64], current: &[u64]) -> ChartDiff {
+    let before: HashSet<u64> = previous.iter().copied().collect();
+    let after: HashSet<u64> = current.iter().copied().collect();
+
+    ChartDiff {
+        first_view: false,
+        new_entries: current
+            .iter()
+            .copied()
+            .filter(|id| !before.contains(id))
+            .collect(),
+        dropped: previous
+            .iter()
+            .copied()
+            .filter(|id| !after.contains(id))
+            .collect(),
+    }
+}
+
+pub fn get_chart_store() -> &'static ChartStore {
+    CHART_STORE.get_or_init(|| {
+        ChartStore::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize chart store: {}", e))
+    })
+}
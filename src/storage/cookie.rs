@@ -62,7 +62,6 @@ impl CookieManager {
         }
     }
 
-    #[allow(dead_code)]
     pub fn has_login_cookie(&self) -> bool {
         self.store
             .read()
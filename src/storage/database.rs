@@ -1,16 +1,12 @@
 use sled::Db;
+use std::path::PathBuf;
 use std::sync::OnceLock;
 
 static DB: OnceLock<Db> = OnceLock::new();
 
 pub fn get_db() -> &'static Db {
     DB.get_or_init(|| {
-        let db_path = dirs::data_local_dir()
-            .or_else(dirs::data_dir)
-            .or_else(dirs::home_dir)
-            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| ".".into()))
-            .join("cloubit")
-            .join("database");
+        let db_path = database_path();
 
         if let Some(parent) = db_path.parent() {
             if let Err(e) = std::fs::create_dir_all(parent) {
@@ -22,10 +18,29 @@ pub fn get_db() -> &'static Db {
             .path(&db_path)
             .cache_capacity(64 * 1024 * 1024)
             .flush_every_ms(Some(1000))
-            .compression_factor(4);
+            .compression_factor(4)
+            .temporary(cfg!(test));
 
         config.open().unwrap_or_else(|e| {
             panic!("Could not open database: {}", e);
         })
     })
 }
+
+#[cfg(not(test))]
+fn database_path() -> PathBuf {
+    dirs::data_local_dir()
+        .or_else(dirs::data_dir)
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| ".".into()))
+        .join("cloubit")
+        .join("database")
+}
+
+/// 测试使用独立的临时数据库，不读写用户数据
+#[cfg(test)]
+fn database_path() -> PathBuf {
+    std::env::temp_dir()
+        .join(format!("cloubit-test-{}", std::process::id()))
+        .join("database")
+}
@@ -0,0 +1,56 @@
+use log::warn;
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+use crate::models::dsp::EqPreset;
+
+/// 用户保存的均衡器预设，以名称为键
+#[derive(Clone)]
+pub struct EqPresetStore {
+    tree: Tree,
+}
+
+static EQ_PRESET_STORE: OnceLock<EqPresetStore> = OnceLock::new();
+
+impl EqPresetStore {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("eq_presets")?,
+        })
+    }
+
+    /// 按名称排序
+    pub fn list(&self) -> Vec<EqPreset> {
+        self.tree
+            .iter()
+            .values()
+            .filter_map(|value| value.ok())
+            .filter_map(|value| serde_json::from_slice(&value).ok())
+            .collect()
+    }
+
+    fn try_save(&self, preset: &EqPreset) -> Result<(), AppError> {
+        self.tree
+            .insert(preset.name.as_bytes(), serde_json::to_vec(preset)?)?;
+        Ok(())
+    }
+
+    /// 同名预设会被覆盖
+    pub fn save(&self, preset: &EqPreset) {
+        if let Err(e) = self.try_save(preset) {
+            warn!("Failed to save equalizer preset {}: {}", preset.name, e);
+        }
+    }
+
+    pub fn remove(&self, name: &str) {
+        let _ = self.tree.remove(name);
+    }
+}
+
+pub fn get_eq_preset_store() -> &'static EqPresetStore {
+    EQ_PRESET_STORE.get_or_init(|| {
+        EqPresetStore::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize equalizer preset store: {}", e))
+    })
+}
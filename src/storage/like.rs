@@ -0,0 +1,118 @@
+use log::warn;
+use sled::{Db, Tree};
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::error::AppError;
+
+/// 本地缓存的"喜欢"列表，以及离线时未能提交的喜欢/取消喜欢操作
+#[derive(Clone)]
+pub struct LikeStore {
+    liked: Arc<RwLock<HashSet<u64>>>,
+    liked_tree: Tree,
+    pending_tree: Tree,
+}
+
+static LIKE_STORE: OnceLock<LikeStore> = OnceLock::new();
+
+impl LikeStore {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        let liked_tree = db.open_tree("liked_songs")?;
+        let pending_tree = db.open_tree("pending_likes")?;
+
+        let liked = liked_tree
+            .iter()
+            .keys()
+            .filter_map(|key| key.ok())
+            .filter_map(|key| decode_id(&key))
+            .collect();
+
+        Ok(Self {
+            liked: Arc::new(RwLock::new(liked)),
+            liked_tree,
+            pending_tree,
+        })
+    }
+
+    pub fn is_liked(&self, id: u64) -> bool {
+        self.liked
+            .read()
+            .map(|liked| liked.contains(&id))
+            .unwrap_or(false)
+    }
+
+    pub fn len(&self) -> usize {
+        self.liked.read().map(|liked| liked.len()).unwrap_or(0)
+    }
+
+    pub fn set_liked(&self, id: u64, like: bool) {
+        if let Ok(mut liked) = self.liked.write() {
+            let result = if like {
+                liked.insert(id);
+                self.liked_tree.insert(id.to_be_bytes(), &[])
+            } else {
+                liked.remove(&id);
+                self.liked_tree.remove(id.to_be_bytes())
+            };
+            if let Err(e) = result {
+                warn!("Failed to persist like state of {}: {}", id, e);
+            }
+        }
+    }
+
+    /// 用服务端的完整列表更新本地缓存，只写入有变化的部分
+    ///
+    /// 尚未提交的本地操作优先于服务端状态，返回 `(新增数, 移除数)`
+    pub fn apply_remote(&self, remote: &[u64]) -> (usize, usize) {
+        let pending: Vec<(u64, bool)> = self.pending();
+        let mut remote: HashSet<u64> = remote.iter().copied().collect();
+        for (id, like) in pending {
+            if like {
+                remote.insert(id);
+            } else {
+                remote.remove(&id);
+            }
+        }
+
+        let (added, removed): (Vec<u64>, Vec<u64>) = match self.liked.read() {
+            Ok(liked) => (
+                remote.difference(&liked).copied().collect(),
+                liked.difference(&remote).copied().collect(),
+            ),
+            Err(_) => return (0, 0),
+        };
+
+        added.iter().for_each(|id| self.set_liked(*id, true));
+        removed.iter().for_each(|id| self.set_liked(*id, false));
+        (added.len(), removed.len())
+    }
+
+    pub fn queue_pending(&self, id: u64, like: bool) {
+        if let Err(e) = self.pending_tree.insert(id.to_be_bytes(), &[like as u8]) {
+            warn!("Failed to queue pending like of {}: {}", id, e);
+        }
+    }
+
+    pub fn clear_pending(&self, id: u64) {
+        let _ = self.pending_tree.remove(id.to_be_bytes());
+    }
+
+    pub fn pending(&self) -> Vec<(u64, bool)> {
+        self.pending_tree
+            .iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|(key, value)| Some((decode_id(&key)?, value.first() == Some(&1))))
+            .collect()
+    }
+}
+
+fn decode_id(bytes: &[u8]) -> Option<u64> {
+    bytes.try_into().ok().map(u64::from_be_bytes)
+}
+
+pub fn get_like_store() -> &'static LikeStore {
+    LIKE_STORE.get_or_init(|| {
+        LikeStore::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize like store: {}", e))
+    })
+}
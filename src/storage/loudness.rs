@@ -0,0 +1,85 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+use crate::models::audio::Loudness;
+
+/// 专辑内已测量曲目的能量累计，用于计算专辑响度
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct AlbumLoudness {
+    energy: f64,
+    peak: f32,
+    tracks: u32,
+}
+
+/// 播放时测量到的响度，以歌曲ID为键
+#[derive(Clone)]
+pub struct LoudnessStore {
+    tracks: Tree,
+    albums: Tree,
+}
+
+static LOUDNESS_STORE: OnceLock<LoudnessStore> = OnceLock::new();
+
+impl LoudnessStore {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tracks: db.open_tree("track_loudness")?,
+            albums: db.open_tree("album_loudness")?,
+        })
+    }
+
+    fn read<T: for<'de> Deserialize<'de>>(tree: &Tree, id: u64) -> Option<T> {
+        let data = tree.get(id.to_be_bytes()).ok()??;
+        serde_json::from_slice(&data).ok()
+    }
+
+    pub fn get(&self, song_id: u64) -> Option<Loudness> {
+        Self::read(&self.tracks, song_id)
+    }
+
+    /// 专辑中已测量曲目的整体响度
+    pub fn album(&self, album_id: u64) -> Option<Loudness> {
+        if album_id == 0 {
+            return None;
+        }
+        let album: AlbumLoudness = Self::read(&self.albums, album_id)?;
+        (album.tracks > 0).then(|| Loudness {
+            integrated: -0.691 + 10.0 * (album.energy / f64::from(album.tracks)).log10(),
+            peak: album.peak,
+        })
+    }
+
+    fn try_save(&self, song_id: u64, album_id: u64, loudness: Loudness) -> Result<(), AppError> {
+        let previous = self
+            .tracks
+            .insert(song_id.to_be_bytes(), serde_json::to_vec(&loudness)?)?;
+        if previous.is_some() || album_id == 0 {
+            return Ok(());
+        }
+
+        let mut album: AlbumLoudness = Self::read(&self.albums, album_id).unwrap_or_default();
+        album.energy += 10f64.powf((loudness.integrated + 0.691) / 10.0);
+        album.peak = album.peak.max(loudness.peak);
+        album.tracks += 1;
+        self.albums
+            .insert(album_id.to_be_bytes(), serde_json::to_vec(&album)?)?;
+        Ok(())
+    }
+
+    /// 保存测量结果，已经测量过的歌曲不重复计入专辑
+    pub fn save(&self, song_id: u64, album_id: u64, loudness: Loudness) {
+        if let Err(e) = self.try_save(song_id, album_id, loudness) {
+            warn!("Failed to save loudness of {}: {}", song_id, e);
+        }
+    }
+}
+
+pub fn get_loudness_store() -> &'static LoudnessStore {
+    LOUDNESS_STORE.get_or_init(|| {
+        LoudnessStore::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize loudness store: {}", e))
+    })
+}
@@ -1,3 +1,13 @@
 pub mod cache;
+pub mod chart;
 pub mod cookie;
 pub mod database;
+pub mod eq_preset;
+pub mod like;
+pub mod loudness;
+pub mod progress;
+pub mod queue;
+pub mod scrobble;
+pub mod search_history;
+pub mod settings;
+pub mod upload;
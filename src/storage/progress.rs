@@ -0,0 +1,49 @@
+use log::warn;
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::error::AppError;
+
+/// 电台节目的收听进度，以节目音频的歌曲ID为键，值为毫秒
+#[derive(Clone)]
+pub struct ProgressStore {
+    tree: Tree,
+}
+
+static PROGRESS_STORE: OnceLock<ProgressStore> = OnceLock::new();
+
+impl ProgressStore {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("program_progress")?,
+        })
+    }
+
+    pub fn get(&self, song_id: u64) -> Option<Duration> {
+        let value = self.tree.get(song_id.to_be_bytes()).ok()??;
+        let millis = u64::from_be_bytes(value.as_ref().try_into().ok()?);
+        Some(Duration::from_millis(millis))
+    }
+
+    pub fn save(&self, song_id: u64, position: Duration) {
+        let millis = position.as_millis() as u64;
+        if let Err(e) = self
+            .tree
+            .insert(song_id.to_be_bytes(), &millis.to_be_bytes())
+        {
+            warn!("Failed to save progress of {}: {}", song_id, e);
+        }
+    }
+
+    pub fn remove(&self, song_id: u64) {
+        let _ = self.tree.remove(song_id.to_be_bytes());
+    }
+}
+
+pub fn get_progress_store() -> &'static ProgressStore {
+    PROGRESS_STORE.get_or_init(|| {
+        ProgressStore::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize progress store: {}", e))
+    })
+}
@@ -0,0 +1,48 @@
+use log::warn;
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+
+use crate::audio::queue::Queue;
+use crate::error::AppError;
+
+const QUEUE_KEY: &str = "queue";
+
+/// 持久化的播放队列，下次启动时恢复
+#[derive(Clone)]
+pub struct QueueStore {
+    tree: Tree,
+}
+
+static QUEUE_STORE: OnceLock<QueueStore> = OnceLock::new();
+
+impl QueueStore {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("play_queue")?,
+        })
+    }
+
+    pub fn load(&self) -> Option<Queue> {
+        self.tree
+            .get(QUEUE_KEY)
+            .ok()
+            .flatten()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+    }
+
+    pub fn save(&self, queue: &Queue) {
+        let result = serde_json::to_vec(queue)
+            .map_err(AppError::from)
+            .and_then(|data| Ok(self.tree.insert(QUEUE_KEY, data)?));
+        if let Err(e) = result {
+            warn!("Failed to save play queue: {}", e);
+        }
+    }
+}
+
+pub fn get_queue_store() -> &'static QueueStore {
+    QUEUE_STORE.get_or_init(|| {
+        QueueStore::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize queue store: {}", e))
+    })
+}
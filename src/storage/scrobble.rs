@@ -0,0 +1,65 @@
+use log::warn;
+use serde_json::Value;
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+
+/// 离线时未能上报的听歌记录，按写入顺序保存
+#[derive(Clone)]
+pub struct ScrobbleQueue {
+    db: Db,
+    tree: Tree,
+}
+
+static SCROBBLE_QUEUE: OnceLock<ScrobbleQueue> = OnceLock::new();
+
+impl ScrobbleQueue {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("scrobble_queue")?,
+            db: db.clone(),
+        })
+    }
+
+    pub fn push(&self, log: &Value) {
+        if let Err(e) = self.try_push(log) {
+            warn!("Failed to queue scrobble: {}", e);
+        }
+    }
+
+    fn try_push(&self, log: &Value) -> Result<(), AppError> {
+        // 单调递增的 ID 保证按写入顺序遍历
+        let id = self.db.generate_id()?;
+        self.tree
+            .insert(id.to_be_bytes(), serde_json::to_vec(log)?)?;
+        Ok(())
+    }
+
+    /// 取出最早的 `limit` 条记录，上报成功后需调用 [`ScrobbleQueue::remove`]
+    pub fn peek(&self, limit: usize) -> Vec<(sled::IVec, Value)> {
+        self.tree
+            .iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|(key, value)| Some((key, serde_json::from_slice(&value).ok()?)))
+            .take(limit)
+            .collect()
+    }
+
+    pub fn remove(&self, keys: &[sled::IVec]) {
+        for key in keys {
+            let _ = self.tree.remove(key);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+}
+
+pub fn get_scrobble_queue() -> &'static ScrobbleQueue {
+    SCROBBLE_QUEUE.get_or_init(|| {
+        ScrobbleQueue::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize scrobble queue: {}", e))
+    })
+}
@@ -0,0 +1,76 @@
+use log::warn;
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::AppError;
+
+/// 最多保留的搜索记录数
+const MAX_HISTORY: usize = 50;
+
+/// 本地搜索记录，以关键词为键、最近一次搜索的时间为值
+#[derive(Clone)]
+pub struct SearchHistory {
+    tree: Tree,
+}
+
+static SEARCH_HISTORY: OnceLock<SearchHistory> = OnceLock::new();
+
+impl SearchHistory {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("search_history")?,
+        })
+    }
+
+    /// 按最近搜索时间倒序排列
+    pub fn list(&self) -> Vec<String> {
+        let mut entries: Vec<(u128, String)> = self
+            .tree
+            .iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|(key, value)| {
+                let time = u128::from_be_bytes(value.as_ref().try_into().ok()?);
+                Some((time, String::from_utf8(key.to_vec()).ok()?))
+            })
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.0));
+        entries.into_iter().map(|(_, keyword)| keyword).collect()
+    }
+
+    pub fn add(&self, keyword: &str) {
+        let keyword = keyword.trim();
+        if keyword.is_empty() {
+            return;
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        if let Err(e) = self.tree.insert(keyword, &now.to_be_bytes()) {
+            warn!("Failed to save search history: {}", e);
+            return;
+        }
+
+        for keyword in self.list().iter().skip(MAX_HISTORY) {
+            self.remove(keyword);
+        }
+    }
+
+    pub fn remove(&self, keyword: &str) {
+        let _ = self.tree.remove(keyword);
+    }
+
+    pub fn clear(&self) {
+        if let Err(e) = self.tree.clear() {
+            warn!("Failed to clear search history: {}", e);
+        }
+    }
+}
+
+pub fn get_search_history() -> &'static SearchHistory {
+    SEARCH_HISTORY.get_or_init(|| {
+        SearchHistory::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize search history: {}", e))
+    })
+}
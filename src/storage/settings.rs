@@ -0,0 +1,52 @@
+use log::warn;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+
+/// 用户设置，以 JSON 形式按键保存
+#[derive(Clone)]
+pub struct Settings {
+    tree: Tree,
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+impl Settings {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("settings")?,
+        })
+    }
+
+    /// 读取设置，不存在或无法解析时返回 `None`
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.tree
+            .get(key)
+            .ok()
+            .flatten()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+    }
+
+    pub fn get_or_default<T: DeserializeOwned + Default>(&self, key: &str) -> T {
+        self.get(key).unwrap_or_default()
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) {
+        let result = serde_json::to_vec(value)
+            .map_err(AppError::from)
+            .and_then(|data| Ok(self.tree.insert(key, data)?));
+        if let Err(e) = result {
+            warn!("Failed to save setting {}: {}", key, e);
+        }
+    }
+}
+
+pub fn get_settings() -> &'static Settings {
+    SETTINGS.get_or_init(|| {
+        Settings::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize settings: {}", e))
+    })
+}
@@ -0,0 +1,50 @@
+use log::warn;
+use sled::{Db, Tree};
+use std::sync::OnceLock;
+
+use crate::error::AppError;
+use crate::models::cloud::UploadSession;
+
+/// 未完成的云盘上传，以文件 MD5 为键
+#[derive(Clone)]
+pub struct UploadStore {
+    tree: Tree,
+}
+
+static UPLOAD_STORE: OnceLock<UploadStore> = OnceLock::new();
+
+impl UploadStore {
+    pub fn new(db: &Db) -> Result<Self, AppError> {
+        Ok(Self {
+            tree: db.open_tree("cloud_uploads")?,
+        })
+    }
+
+    pub fn get(&self, md5: &str) -> Option<UploadSession> {
+        self.tree
+            .get(md5)
+            .ok()
+            .flatten()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+    }
+
+    pub fn save(&self, md5: &str, session: &UploadSession) {
+        let result = serde_json::to_vec(session)
+            .map_err(AppError::from)
+            .and_then(|data| Ok(self.tree.insert(md5, data)?));
+        if let Err(e) = result {
+            warn!("Failed to save upload session {}: {}", md5, e);
+        }
+    }
+
+    pub fn remove(&self, md5: &str) {
+        let _ = self.tree.remove(md5);
+    }
+}
+
+pub fn get_upload_store() -> &'static UploadStore {
+    UPLOAD_STORE.get_or_init(|| {
+        UploadStore::new(crate::get_db())
+            .unwrap_or_else(|e| panic!("Failed to initialize upload store: {}", e))
+    })
+}
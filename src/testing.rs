@@ -0,0 +1,311 @@
+//! 测试用的本地 HTTP 服务，只实现用例需要的 HTTP/1.1 子集
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::models::song::SongUrl;
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn param(&self, name: &str) -> Option<String> {
+        self.query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key == name).then(|| urlencoding::decode(value).unwrap_or_default().into_owned())
+        })
+    }
+}
+
+pub struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn json(value: serde_json::Value) -> Self {
+        Self::new(200, value.to_string()).header("Content-Type", "application/json")
+    }
+
+    pub fn header(mut self, name: &str, value: impl ToString) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+type Handler = dyn Fn(&Request) -> Response + Send + Sync;
+
+/// 在随机端口上监听，每个连接只处理一个请求
+pub struct MockServer {
+    url: String,
+}
+
+impl MockServer {
+    pub fn start(handler: impl Fn(&Request) -> Response + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let handler = handler.clone();
+                thread::spawn(move || {
+                    if let Some(request) = read_request(&stream) {
+                        write_response(stream, &request, handler(&request));
+                    }
+                });
+            }
+        });
+
+        Self { url }
+    }
+
+    /// 按 Range 请求返回文件的一部分，与音频 CDN 的行为一致
+    pub fn file(data: Vec<u8>) -> Self {
+        let length = data.len();
+        Self::start(move |request| range_response(&data, length, request))
+    }
+
+    /// 声称的总长度 `length` 大于实际的数据，模拟连接中途断开的文件
+    pub fn truncated_file(data: Vec<u8>, length: usize) -> Self {
+        Self::start(move |request| range_response(&data, length, request))
+    }
+
+    /// 从 `offset` 字节起的请求都返回 500，模拟播放中途断网
+    pub fn failing_file(data: Vec<u8>, offset: usize) -> Self {
+        let length = data.len();
+        Self::start(move |request| {
+            let start = range(request).map_or(0, |(start, _)| start);
+            if start >= offset {
+                return Response::new(500, "");
+            }
+            range_response(&data, length, request)
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// 测试中歌曲ID对应的播放地址
+static SONG_URLS: Mutex<BTreeMap<u64, String>> = Mutex::new(BTreeMap::new());
+
+/// 播放器解析该歌曲时直接使用 `url`，不请求网易云的接口
+pub fn register_song(id: u64, url: &str) {
+    SONG_URLS.lock().unwrap().insert(id, url.to_string());
+}
+
+pub fn song_url(id: u64) -> Option<SongUrl> {
+    let url = SONG_URLS.lock().unwrap().get(&id)?.clone();
+    serde_json::from_value(serde_json::json!({ "id": id, "url": url })).ok()
+}
+
+/// 请求的字节范围，结束位置不含在内
+fn range(request: &Request) -> Option<(usize, Option<usize>)> {
+    let (start, end) = request
+        .header("Range")?
+        .strip_prefix("bytes=")?
+        .split_once('-')?;
+    Some((
+        start.parse().unwrap_or(0),
+        end.parse::<usize>().ok().map(|end| end + 1),
+    ))
+}
+
+fn range_response(data: &[u8], length: usize, request: &Request) -> Response {
+    let Some((start, end)) = range(request) else {
+        return Response::new(200, data.to_vec());
+    };
+    let end = end.unwrap_or(data.len()).min(data.len());
+    if start >= end {
+        return Response::new(416, "").header("Content-Range", format!("bytes */{}", length));
+    }
+    Response::new(206, data[start..end].to_vec()).header(
+        "Content-Range",
+        format!("bytes {}-{}/{}", start, end - 1, length),
+    )
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (path, query) = (path.to_string(), query.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(Request {
+        method,
+        path,
+        query,
+        headers,
+        body,
+    })
+}
+
+fn write_response(mut stream: TcpStream, request: &Request, response: Response) {
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (key, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes());
+    if request.method != "HEAD" {
+        let _ = stream.write_all(&response.body);
+    }
+}
+
+/// 测试用 FLAC 每帧的采样数
+pub const FLAC_BLOCK: u64 = 4096;
+
+/// 44.1kHz 16 位双声道的 FLAC，采样值记录了自身的位置：
+/// 左声道为序号的高位，右声道为低 14 位，见 [`flac_position`]
+pub fn flac(frames: u64) -> Vec<u8> {
+    let total = frames * FLAC_BLOCK;
+    let mut data = b"fLaC".to_vec();
+    // 最后一个元数据块，类型为 STREAMINFO，长度 34
+    data.extend([0x80, 0, 0, 34]);
+    data.extend((FLAC_BLOCK as u16).to_be_bytes());
+    data.extend((FLAC_BLOCK as u16).to_be_bytes());
+    data.extend([0; 6]);
+    data.extend((44100u64 << 44 | 1 << 41 | 15 << 36 | total).to_be_bytes());
+    data.extend([0; 16]);
+
+    for index in 0..frames {
+        assert!(index < 0x80, "frame number must fit in one byte");
+        // 固定块大小，4096 个采样，44.1kHz，独立双声道，16 位
+        let mut frame = vec![0xFF, 0xF8, 0xC9, 0x18, index as u8];
+        frame.push(crc8(&frame));
+        for channel in 0..2 {
+            // 原样存储的子帧
+            frame.push(0x02);
+            for n in index * FLAC_BLOCK..(index + 1) * FLAC_BLOCK {
+                let sample = if channel == 0 { n >> 14 } else { n & 0x3FFF };
+                frame.extend((sample as i16).to_be_bytes());
+            }
+        }
+        frame.extend(crc16(&frame).to_be_bytes());
+        data.extend(frame);
+    }
+    data
+}
+
+/// 由 [`flac`] 解码出的一对采样还原其位置
+pub fn flac_position(left: f32, right: f32) -> u64 {
+    let (high, low) = ((left * 32768.0).round(), (right * 32768.0).round());
+    (high as u64) << 14 | low as u64
+}
+
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                crc << 1 ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ u16::from(*byte) << 8, |crc, _| {
+            if crc & 0x8000 != 0 {
+                crc << 1 ^ 0x8005
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+/// 测试用 MP3 每帧的采样数
+pub const MP3_FRAME: u64 = 1152;
+
+/// 44.1kHz 单声道的 MPEG-1 Layer III 静音帧，按给出的码率（kbps）依次生成；
+/// 首帧为记录帧数的 Xing 头，码率各不相同时即为 VBR 文件
+pub fn mp3(bitrates: &[u32]) -> Vec<u8> {
+    const BITRATES: [u32; 15] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    // 边信息全为 0 的帧解码为静音
+    let frame = |bitrate: u32| {
+        let index = BITRATES
+            .iter()
+            .position(|b| *b == bitrate)
+            .expect("unsupported bitrate");
+        let mut frame = vec![0u8; (144_000 * bitrate / 44100) as usize];
+        // 无 CRC，44.1kHz，无填充，单声道
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, (index as u8) << 4, 0xC0]);
+        frame
+    };
+
+    let audio: Vec<u8> = bitrates
+        .iter()
+        .flat_map(|bitrate| frame(*bitrate))
+        .collect();
+    let mut data = frame(128);
+    // Xing 头位于单声道 17 字节的边信息之后，只包含帧数与字节数
+    let xing = 4 + 17;
+    let length = (data.len() + audio.len()) as u32;
+    data[xing..xing + 4].copy_from_slice(b"Xing");
+    data[xing + 4..xing + 8].copy_from_slice(&3u32.to_be_bytes());
+    data[xing + 8..xing + 12].copy_from_slice(&(bitrates.len() as u32).to_be_bytes());
+    data[xing + 12..xing + 16].copy_from_slice(&length.to_be_bytes());
+    data.extend(audio);
+    data
+}
@@ -0,0 +1,58 @@
+use log::{error, info};
+use std::process::Command;
+use std::thread;
+
+use crate::audio::engine::get_backend;
+use crate::error::AppError;
+use crate::models::audio::BackendState;
+use crate::models::video::{VideoPlayerConfig, VideoStream};
+use crate::service::video::{VIDEO_REFERER, VIDEO_USER_AGENT};
+use crate::storage::settings::get_settings;
+
+const PLAYER_SETTING: &str = "video_player";
+
+fn send(command: BackendState) {
+    if let Err(e) = get_backend().command_sender.send(command) {
+        error!("Failed to send audio command: {}", e);
+    }
+}
+
+pub fn get_player_config() -> VideoPlayerConfig {
+    get_settings().get_or_default(PLAYER_SETTING)
+}
+
+pub fn set_player_config(config: &VideoPlayerConfig) {
+    get_settings().set(PLAYER_SETTING, config);
+}
+
+fn build_command(config: &VideoPlayerConfig, stream: &VideoStream) -> Command {
+    let mut command = Command::new(&config.program);
+    command.args(config.args.iter().map(|arg| {
+        arg.replace("{url}", &stream.url)
+            .replace("{title}", &stream.title)
+            .replace("{referer}", VIDEO_REFERER)
+            .replace("{user_agent}", VIDEO_USER_AGENT)
+    }));
+    command
+}
+
+/// 使用外部播放器播放视频，播放期间暂停音乐
+pub fn play(stream: &VideoStream) -> Result<(), AppError> {
+    let config = get_settings().get_or_default::<VideoPlayerConfig>(PLAYER_SETTING);
+    let mut child = build_command(&config, stream)
+        .spawn()
+        .map_err(|e| AppError::Common(format!("Failed to start {}: {}", config.program, e)))?;
+    info!(
+        "Playing {} ({}p) with {}",
+        stream.title, stream.resolution, config.program
+    );
+
+    send(BackendState::Suspend(true));
+    thread::spawn(move || {
+        if let Err(e) = child.wait() {
+            error!("Failed to wait for video player: {}", e);
+        }
+        send(BackendState::Suspend(false));
+    });
+    Ok(())
+}